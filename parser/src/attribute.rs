@@ -0,0 +1,130 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::name::FullyQualifiedName;
+
+/// A PHP attribute, e.g. `#[\SensitiveParameter]` or `#[\Deprecated(since: '8.4')]`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Attribute {
+    pub name: FullyQualifiedName,
+    /// Raw source of each argument, e.g. `since: '8.4'`
+    pub arguments: Vec<String>,
+}
+
+impl Attribute {
+    pub const SENSITIVE_PARAMETER: &'static str = "SensitiveParameter";
+    pub const DEPRECATED: &'static str = "Deprecated";
+
+    /// Whether this attribute is an instance of the given class, case-insensitively (like PHP)
+    pub fn is(&self, name: &str) -> bool {
+        self.name
+            .to_string()
+            .eq_ignore_ascii_case(name.trim_start_matches('\\'))
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#[\\{}", self.name)?;
+        if !self.arguments.is_empty() {
+            write!(f, "({})", self.arguments.join(", "))?;
+        }
+
+        write!(f, "]")
+    }
+}
+
+impl From<&str> for Attribute {
+    fn from(value: &str) -> Self {
+        let value = value.trim();
+        let value = value
+            .strip_prefix("#[")
+            .and_then(|value| value.strip_suffix(']'))
+            .unwrap_or(value)
+            .trim();
+
+        let (name, arguments) = match value.split_once('(') {
+            Some((name, arguments)) => (
+                name,
+                split_arguments(arguments.trim_end().strip_suffix(')').unwrap_or(arguments)),
+            ),
+            None => (value, Vec::new()),
+        };
+
+        Self {
+            name: FullyQualifiedName::from(name),
+            arguments,
+        }
+    }
+}
+
+/// Splits an argument list on its top-level commas, ignoring the ones nested
+/// in brackets or string literals
+fn split_arguments(arguments: &str) -> Vec<String> {
+    let mut result = Vec::<String>::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote = Option::<char>::None;
+    let mut escaped = false;
+
+    for char in arguments.chars() {
+        match (quote, char) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(opening), char) if opening == char => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(char),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                result.push(std::mem::take(&mut current).trim().to_string());
+                continue;
+            }
+            (None, _) => {}
+        }
+
+        current.push(char);
+    }
+
+    if !current.trim().is_empty() {
+        result.push(current.trim().to_string());
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::Attribute;
+
+    #[rstest::rstest]
+    #[case("#[\\SensitiveParameter]", "SensitiveParameter", &[])]
+    #[case(" #[\\ReturnTypeWillChange] ", "ReturnTypeWillChange", &[])]
+    #[case(
+        "#[\\Deprecated(since: '8.4', message: \"use foo(), bar() instead\")]",
+        "Deprecated",
+        &["since: '8.4'", "message: \"use foo(), bar() instead\""]
+    )]
+    #[case("#[\\Attribute(Attribute::TARGET_CLASS | [1, 2])]", "Attribute", &["Attribute::TARGET_CLASS | [1, 2]"])]
+    pub fn test_attribute_parsing(
+        #[case] source: &str,
+        #[case] name: &str,
+        #[case] arguments: &[&str],
+    ) {
+        let attribute = Attribute::from(source);
+
+        assert_eq!(attribute.name.to_string(), name);
+        assert_eq!(attribute.arguments, arguments);
+        assert_eq!(attribute.to_string(), source.trim());
+    }
+
+    #[test]
+    pub fn test_attribute_is_case_insensitive() {
+        let attribute = Attribute::from("#[\\SensitiveParameter]");
+
+        assert!(attribute.is(Attribute::SENSITIVE_PARAMETER));
+        assert!(attribute.is("\\sensitiveparameter"));
+        assert!(!attribute.is(Attribute::DEPRECATED));
+    }
+}
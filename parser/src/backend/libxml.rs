@@ -0,0 +1,66 @@
+use std::borrow::Cow;
+
+use libxml::{
+    parser::Parser,
+    tree::{self, NodeType},
+};
+
+use crate::{
+    XmlError,
+    dom::{Element, Node},
+};
+
+pub(super) fn parse(content: &[u8]) -> Result<Element, XmlError> {
+    let document = Parser::default()
+        .parse_string(with_external_subset(content))
+        .map_err(XmlError::ParseError)?;
+
+    let root = document
+        .get_root_element()
+        .ok_or(XmlError::MalformedXmlDefinition("root element"))?;
+
+    Ok(element(&root))
+}
+
+/// libxml keeps references to undeclared entities only in documents with an external subset, as
+/// pages of the manual get theirs from the build. Without one, it drops the reference along with
+/// every entity after it, even `&amp;`. The subset is declared but never loaded
+fn with_external_subset(content: &[u8]) -> Cow<'_, [u8]> {
+    const DOCTYPE: &[u8] = br#"<!DOCTYPE book SYSTEM "entities.ent">"#;
+
+    if content.windows(9).any(|window| window == b"<!DOCTYPE") {
+        return Cow::Borrowed(content);
+    }
+
+    // The doctype must follow the XML declaration, if any
+    let start = if content.starts_with(b"<?xml") {
+        content
+            .windows(2)
+            .position(|window| window == b"?>")
+            .map_or(0, |end| end + 2)
+    } else {
+        0
+    };
+
+    Cow::Owned([&content[..start], DOCTYPE, &content[start..]].concat())
+}
+
+fn element(node: &tree::Node) -> Element {
+    Element {
+        name: node.get_name(),
+        attributes: node.get_attributes().into_iter().collect(),
+        children: node
+            .get_child_nodes()
+            .iter()
+            .filter_map(|child| match child.get_type()? {
+                NodeType::ElementNode => Some(Node::Element(element(child))),
+                NodeType::TextNode | NodeType::CDataSectionNode => {
+                    Some(Node::Text(child.get_content()))
+                }
+                NodeType::EntityRefNode => Some(Node::EntityReference(child.get_name())),
+                NodeType::CommentNode => Some(Node::Comment(child.get_content())),
+                _ => None,
+            })
+            .collect(),
+    }
+}
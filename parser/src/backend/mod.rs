@@ -0,0 +1,45 @@
+#[cfg(feature = "backend-libxml")]
+mod libxml;
+#[cfg(feature = "backend-pure")]
+mod pure;
+
+#[cfg(not(any(feature = "backend-libxml", feature = "backend-pure")))]
+compile_error!("At least one of the `backend-libxml` or `backend-pure` features must be enabled");
+
+use super::{XmlError, dom::Element};
+
+/// Reads XML documents into a [`Element`] tree, every backend produces the same tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// `libxml2`, through the `libxml` crate
+    #[cfg(feature = "backend-libxml")]
+    Libxml,
+    /// `quick-xml`, a streaming reader written in Rust which does not need any system library
+    #[cfg(feature = "backend-pure")]
+    Pure,
+}
+
+impl Backend {
+    /// Every backend enabled at compile time, by order of preference
+    pub const ALL: &[Backend] = &[
+        #[cfg(feature = "backend-libxml")]
+        Backend::Libxml,
+        #[cfg(feature = "backend-pure")]
+        Backend::Pure,
+    ];
+
+    pub(crate) fn parse(self, content: &[u8]) -> Result<Element, XmlError> {
+        match self {
+            #[cfg(feature = "backend-libxml")]
+            Backend::Libxml => libxml::parse(content),
+            #[cfg(feature = "backend-pure")]
+            Backend::Pure => pure::parse(content),
+        }
+    }
+}
+
+impl Default for Backend {
+    fn default() -> Self {
+        Self::ALL[0]
+    }
+}
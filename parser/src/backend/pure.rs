@@ -0,0 +1,209 @@
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
+
+use crate::{
+    XmlError,
+    dom::{Element, Node},
+};
+
+pub(super) fn parse(content: &[u8]) -> Result<Element, XmlError> {
+    let mut reader = Reader::from_reader(content);
+    // Elements which are still open, the innermost last
+    let mut stack = Vec::<Element>::new();
+    let mut root = Option::<Element>::None;
+
+    loop {
+        let nodes = match reader.read_event().map_err(XmlError::ReadError)? {
+            Event::Start(start) => {
+                stack.push(element(&start)?);
+                continue;
+            }
+            Event::Empty(start) => vec![Node::Element(element(&start)?)],
+            Event::End(_) => vec![Node::Element(
+                stack
+                    .pop()
+                    .ok_or(XmlError::MalformedXmlDefinition("closing tag"))?,
+            )],
+            Event::Text(text) => decode_text(&String::from_utf8_lossy(&text)),
+            Event::CData(cdata) => vec![Node::Text(normalize_line_endings(
+                &String::from_utf8_lossy(&cdata),
+            ))],
+            Event::Comment(comment) => vec![Node::Comment(
+                String::from_utf8_lossy(&comment).into_owned(),
+            )],
+            Event::Decl(_) | Event::PI(_) | Event::DocType(_) => continue,
+            Event::Eof => break,
+        };
+
+        match stack.last_mut() {
+            Some(parent) => parent.children.extend(nodes),
+            // Only the root element is kept outside of it, whitespace and comments are dropped
+            None => {
+                for node in nodes {
+                    if let Node::Element(element) = node {
+                        root.get_or_insert(element);
+                    }
+                }
+            }
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(XmlError::MalformedXmlDefinition("closing tag"));
+    }
+
+    root.ok_or(XmlError::MalformedXmlDefinition("root element"))
+}
+
+fn element(start: &BytesStart) -> Result<Element, XmlError> {
+    let mut element = Element {
+        name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+        ..Default::default()
+    };
+
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|error| XmlError::ReadError(error.into()))?;
+        // Namespace declarations are not attributes for libxml either
+        if attribute.key.as_namespace_binding().is_some() {
+            continue;
+        }
+
+        // Whitespace is normalized in attribute values, as per the XML specification
+        let value = String::from_utf8_lossy(&attribute.value).replace(['\t', '\n', '\r'], " ");
+        let value = decode_text(&value)
+            .into_iter()
+            .map(|node| match node {
+                Node::EntityReference(name) => format!("&{name};"),
+                Node::Text(text) => text,
+                _ => String::new(),
+            })
+            .collect();
+
+        element.attributes.insert(
+            String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned(),
+            value,
+        );
+    }
+
+    Ok(element)
+}
+
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Splits raw text around the references to entities the document does not declare, resolving
+/// the predefined and character ones
+fn decode_text(raw: &str) -> Vec<Node> {
+    let raw = normalize_line_endings(raw);
+    let mut nodes = Vec::<Node>::new();
+    let mut text = String::new();
+    let mut rest = raw.as_str();
+
+    while let Some(start) = rest.find('&') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let Some(end) = rest.find(';').filter(|end| is_entity_name(&rest[..*end])) else {
+            text.push('&');
+            continue;
+        };
+
+        let name = &rest[..end];
+        rest = &rest[end + 1..];
+
+        match resolve_entity(name) {
+            Some(char) => text.push(char),
+            None => {
+                if !text.is_empty() {
+                    nodes.push(Node::Text(std::mem::take(&mut text)));
+                }
+                nodes.push(Node::EntityReference(name.to_string()));
+            }
+        }
+    }
+
+    text.push_str(rest);
+    if !text.is_empty() {
+        nodes.push(Node::Text(text));
+    }
+
+    nodes
+}
+
+fn is_entity_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|char| char.is_alphanumeric() || matches!(char, '.' | '-' | '_' | ':' | '#'))
+}
+
+fn resolve_entity(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match name.strip_prefix("#x") {
+                Some(hexadecimal) => u32::from_str_radix(hexadecimal, 16).ok()?,
+                None => name.strip_prefix('#')?.parse::<u32>().ok()?,
+            };
+
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Node, decode_text, parse};
+
+    #[rstest::rstest]
+    #[case("plain", vec![Node::Text("plain".to_string())])]
+    #[case("a &lt;b&gt; &#x41;&#66;", vec![Node::Text("a <b> AB".to_string())])]
+    #[case(
+        "&reftitle.parameters; text",
+        vec![
+            Node::EntityReference("reftitle.parameters".to_string()),
+            Node::Text(" text".to_string()),
+        ]
+    )]
+    #[case("a & b", vec![Node::Text("a & b".to_string())])]
+    pub fn test_text_decoding(#[case] raw: &str, #[case] expected: Vec<Node>) {
+        assert_eq!(decode_text(raw), expected);
+    }
+
+    #[test]
+    pub fn test_document_parsing() {
+        let root = parse(
+            br#"<?xml version="1.0" encoding="utf-8"?>
+<!-- $Revision$ -->
+<refentry xml:id="function.strlen" xmlns="http://docbook.org/ns/docbook" xmlns:xlink="http://www.w3.org/1999/xlink">
+ <refnamediv><refname>strlen</refname><refpurpose>Get string length</refpurpose></refnamediv>
+ <programlisting><![CDATA[<?php echo strlen('a'); ?>]]></programlisting>
+ <simpara>&return.falseforfailure;<!-- comment --></simpara>
+</refentry>"#,
+        )
+        .unwrap();
+
+        assert_eq!(root.name, "refentry");
+        assert_eq!(root.attributes.len(), 1);
+        assert_eq!(root.attribute("id"), Some("function.strlen"));
+        assert_eq!(root.select("refnamediv/refname")[0].content(), "strlen");
+        assert_eq!(
+            root.select("programlisting")[0].content(),
+            "<?php echo strlen('a'); ?>"
+        );
+        assert_eq!(
+            root.select("simpara")[0].children,
+            vec![
+                Node::EntityReference("return.falseforfailure".to_string()),
+                Node::Comment(" comment ".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use super::text::TextNode;
+
+/// A `<chapter>`, `<sect1>`, `<section>`... page of the manual, with its nested sections
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Section {
+    /// The `xml:id` of the section, e.g. `language.oop5.late-static-bindings`
+    pub id: Option<String>,
+    pub title: String,
+    /// Blocks of text (paragraphs, examples, notes...) preceding the nested sections
+    pub paragraphs: Vec<Vec<TextNode>>,
+    pub sections: Vec<Section>,
+}
+
+impl Section {
+    /// What the section is looked up by: its id, as many sections share titles like
+    /// `Introduction`, or its title for the few without one
+    pub fn key(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.title)
+    }
+
+    /// Depth-first list of this section and all of its nested sections, along with their depth
+    pub fn table_of_contents(&self) -> Vec<(usize, &Section)> {
+        let mut entries = vec![(0, self)];
+        for section in &self.sections {
+            entries.extend(
+                section
+                    .table_of_contents()
+                    .into_iter()
+                    .map(|(depth, section)| (depth + 1, section)),
+            );
+        }
+
+        entries
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{attribute::Attribute, name::FullyQualifiedName, text::TextNode};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Class {
+    pub name: FullyQualifiedName,
+    pub kind: ClassKind,
+    /// `final`, `abstract`, `readonly`...
+    pub modifiers: Vec<String>,
+    pub attributes: Vec<Attribute>,
+    pub extends: Option<FullyQualifiedName>,
+    pub implements: Vec<FullyQualifiedName>,
+    pub description: Vec<TextNode>,
+}
+
+#[derive(
+    derive_more::Display,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+pub enum ClassKind {
+    #[default]
+    #[display("class")]
+    Class,
+    #[display("interface")]
+    Interface,
+    #[display("trait")]
+    Trait,
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for attribute in &self.attributes {
+            writeln!(f, "{attribute}")?;
+        }
+
+        for modifier in &self.modifiers {
+            write!(f, "{modifier} ")?;
+        }
+
+        write!(f, "{} {}", self.kind, self.name)?;
+
+        if let Some(extends) = &self.extends {
+            write!(f, " extends {extends}")?;
+        }
+
+        if !self.implements.is_empty() {
+            write!(
+                f,
+                " {} {}",
+                match self.kind {
+                    ClassKind::Interface => "extends",
+                    _ => "implements",
+                },
+                self.implements
+                    .iter()
+                    .map(FullyQualifiedName::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{name::FullyQualifiedName, text::TextNode, r#type::TypeHint};
+
+/// A constant predefined by an extension, e.g. `PHP_ROUND_HALF_UP`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Constant {
+    pub name: FullyQualifiedName,
+    /// `None` for constants listed in a table, which seldom gives it
+    pub r#type: Option<TypeHint>,
+    pub description: Vec<TextNode>,
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.r#type {
+            Some(r#type) => write!(f, "{} ({type})", self.name),
+            None => self.name.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+/// A node of a parsed XML document, independent of the backend which read it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    /// Text and CDATA sections
+    Text(String),
+    /// A reference to an entity the document does not declare, e.g. `&reftitle.parameters;`
+    EntityReference(String),
+    Comment(String),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Element {
+    /// Name of the element, without its namespace prefix
+    pub name: String,
+    /// Attributes by name, without their namespace prefix (`id` for `xml:id`)
+    pub attributes: BTreeMap<String, String>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    /// The text of the element and of all of its descendants
+    pub fn content(&self) -> String {
+        let mut content = String::new();
+        self.push_content(&mut content);
+
+        content
+    }
+
+    fn push_content(&self, content: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Element(element) => element.push_content(content),
+                Node::Text(text) => content.push_str(text),
+                Node::EntityReference(_) | Node::Comment(_) => {}
+            }
+        }
+    }
+
+    pub fn child_elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Elements matching a `/` separated path of element names, relative to this element. Steps
+    /// can filter on an attribute value with `name[attribute=value]`, e.g.
+    /// `refsect1[role=description]/methodsynopsis/type`
+    pub fn select(&self, path: &str) -> Vec<&Element> {
+        path.split('/').fold(vec![self], |elements, step| {
+            let (name, filter) = match step.split_once('[') {
+                Some((name, filter)) => (name, filter.trim_end_matches(']').split_once('=')),
+                None => (step, None),
+            };
+
+            elements
+                .into_iter()
+                .flat_map(Element::child_elements)
+                .filter(|element| {
+                    element.name == name
+                        && filter.is_none_or(|(attribute, value)| {
+                            element.attribute(attribute) == Some(value)
+                        })
+                })
+                .collect()
+        })
+    }
+
+    /// Names of the entities referenced by this element and its descendants, in document order
+    pub fn entity_references(&self) -> Vec<&str> {
+        self.children
+            .iter()
+            .flat_map(|child| match child {
+                Node::Element(element) => element.entity_references(),
+                Node::EntityReference(name) => vec![name.as_str()],
+                Node::Text(_) | Node::Comment(_) => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// This element and all of its descendants named `name`, in document order
+    pub fn find_all(&self, name: &str) -> Vec<&Element> {
+        let mut elements = Vec::<&Element>::new();
+        if self.name == name {
+            elements.push(self);
+        }
+
+        for child in self.child_elements() {
+            elements.extend(child.find_all(name));
+        }
+
+        elements
+    }
+}
@@ -0,0 +1,496 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::symbol::Symbol;
+
+/// A PHP constant expression, as found in parameter default values (`<initializer>`)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Expression {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// Kept as written, since floats are neither `Eq` nor `Ord`
+    Float(String),
+    String(String),
+    Constant(Symbol),
+    ClassConstant {
+        class: Symbol,
+        constant: Symbol,
+    },
+    Array(Vec<ArrayItem>),
+    New {
+        class: String,
+        arguments: Vec<Expression>,
+    },
+    /// `FLAG_A | FLAG_B`
+    BitwiseOr(Vec<Expression>),
+    /// Anything that could not be parsed, as written in the documentation
+    Raw(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ArrayItem {
+    pub key: Option<Expression>,
+    pub value: Expression,
+}
+
+impl Expression {
+    /// Operands of a `|` expression, or the expression itself
+    pub fn flags(&self) -> Vec<&Expression> {
+        match self {
+            Expression::BitwiseOr(operands) => operands.iter().collect(),
+            expression => vec![expression],
+        }
+    }
+
+    /// Names of every constant referenced in the expression, e.g. `JSON_THROW_ON_ERROR` or
+    /// `DateTimeInterface::ATOM`
+    pub fn constants(&self) -> Vec<String> {
+        match self {
+            Expression::Constant(name) => vec![name.to_string()],
+            Expression::ClassConstant { class, constant } => vec![format!("{class}::{constant}")],
+            Expression::Array(items) => items
+                .iter()
+                .flat_map(|item| item.key.iter().chain(Some(&item.value)))
+                .flat_map(Expression::constants)
+                .collect(),
+            Expression::New { arguments, .. } | Expression::BitwiseOr(arguments) => {
+                arguments.iter().flat_map(Expression::constants).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Evaluates an integer expression, looking up constants with `resolve`
+    ///
+    /// Returns `None` if the expression is not an integer, or if a constant could not be resolved
+    pub fn evaluate<Resolver>(&self, resolve: &Resolver) -> Option<i64>
+    where
+        Resolver: Fn(&str) -> Option<i64>,
+    {
+        match self {
+            Expression::Int(value) => Some(*value),
+            Expression::Bool(value) => Some(i64::from(*value)),
+            Expression::Constant(name) => resolve(name),
+            Expression::ClassConstant { class, constant } => {
+                resolve(&format!("{class}::{constant}"))
+            }
+            Expression::BitwiseOr(operands) => operands
+                .iter()
+                .try_fold(0, |flags, operand| Some(flags | operand.evaluate(resolve)?)),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Expression {
+    fn from(value: &str) -> Self {
+        let value = value.trim();
+
+        Lexer::tokenize(value)
+            .and_then(|tokens| {
+                let mut parser = Parser {
+                    tokens,
+                    position: 0,
+                };
+                let expression = parser.expression()?;
+
+                (parser.position == parser.tokens.len()).then_some(expression)
+            })
+            .unwrap_or_else(|| Expression::Raw(value.to_string()))
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Null => write!(f, "null"),
+            Expression::Bool(value) => write!(f, "{value}"),
+            Expression::Int(value) => write!(f, "{value}"),
+            Expression::Float(value) | Expression::Raw(value) => write!(f, "{value}"),
+            Expression::Constant(name) => write!(f, "{name}"),
+            Expression::String(value) => write!(f, "{value:?}"),
+            Expression::ClassConstant { class, constant } => write!(f, "{class}::{constant}"),
+            Expression::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    if let Some(key) = &item.key {
+                        write!(f, "{key} => ")?;
+                    }
+                    write!(f, "{}", item.value)?;
+                }
+                write!(f, "]")
+            }
+            Expression::New { class, arguments } => {
+                write!(f, "new {class}(")?;
+                for (i, argument) in arguments.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{argument}")?;
+                }
+                write!(f, ")")
+            }
+            Expression::BitwiseOr(operands) => {
+                for (i, operand) in operands.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{operand}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Float(String),
+    String(String),
+    Identifier(String),
+    DoubleColon,
+    Pipe,
+    Minus,
+    Comma,
+    DoubleArrow,
+    OpenParenthesis,
+    CloseParenthesis,
+    OpenBracket,
+    CloseBracket,
+}
+
+struct Lexer;
+
+impl Lexer {
+    fn tokenize(source: &str) -> Option<Vec<Token>> {
+        let mut tokens = Vec::<Token>::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(&char) = chars.peek() {
+            let token = match char {
+                char if char.is_whitespace() => {
+                    chars.next();
+                    continue;
+                }
+                '0'..='9' | '.' => {
+                    let mut number = String::new();
+                    while let Some(&char) = chars.peek() {
+                        let is_exponent_sign = matches!(char, '+' | '-')
+                            && number.ends_with(['e', 'E'])
+                            && !number.starts_with("0x");
+                        if !(char.is_ascii_alphanumeric()
+                            || char == '.'
+                            || char == '_'
+                            || is_exponent_sign)
+                        {
+                            break;
+                        }
+                        number.push(char);
+                        chars.next();
+                    }
+
+                    Self::number(&number.replace('_', ""))?
+                }
+                '\'' | '"' => {
+                    chars.next();
+                    let mut string = String::new();
+                    loop {
+                        match chars.next()? {
+                            '\\' => string.push(chars.next()?),
+                            closing if closing == char => break,
+                            other => string.push(other),
+                        }
+                    }
+
+                    Token::String(string)
+                }
+                char if char.is_alphabetic() || char == '_' || char == '\\' => {
+                    let mut identifier = String::new();
+                    while let Some(&char) = chars.peek() {
+                        if !(char.is_alphanumeric() || char == '_' || char == '\\') {
+                            break;
+                        }
+                        identifier.push(char);
+                        chars.next();
+                    }
+
+                    Token::Identifier(identifier)
+                }
+                ':' => {
+                    chars.next();
+                    if chars.next()? != ':' {
+                        return None;
+                    }
+
+                    Token::DoubleColon
+                }
+                '=' => {
+                    chars.next();
+                    if chars.next()? != '>' {
+                        return None;
+                    }
+
+                    Token::DoubleArrow
+                }
+                _ => {
+                    chars.next();
+                    match char {
+                        '|' => Token::Pipe,
+                        '-' => Token::Minus,
+                        ',' => Token::Comma,
+                        '(' => Token::OpenParenthesis,
+                        ')' => Token::CloseParenthesis,
+                        '[' => Token::OpenBracket,
+                        ']' => Token::CloseBracket,
+                        _ => return None,
+                    }
+                }
+            };
+
+            tokens.push(token);
+        }
+
+        Some(tokens)
+    }
+
+    fn number(number: &str) -> Option<Token> {
+        let lowercase = number.to_ascii_lowercase();
+        let (digits, radix) = if let Some(digits) = lowercase.strip_prefix("0x") {
+            (digits, 16)
+        } else if let Some(digits) = lowercase.strip_prefix("0b") {
+            (digits, 2)
+        } else if let Some(digits) = lowercase.strip_prefix("0o") {
+            (digits, 8)
+        } else if lowercase.contains(['.', 'e']) {
+            return number
+                .parse::<f64>()
+                .ok()
+                .map(|_| Token::Float(number.to_string()));
+        } else if lowercase.len() > 1 && lowercase.starts_with('0') {
+            (&lowercase[1..], 8)
+        } else {
+            (lowercase.as_str(), 10)
+        };
+
+        i64::from_str_radix(digits, radix).ok().map(Token::Int)
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matches = self.peek() == Some(token);
+        if matches {
+            self.position += 1;
+        }
+
+        matches
+    }
+
+    fn expect(&mut self, token: &Token) -> Option<()> {
+        self.eat(token).then_some(())
+    }
+
+    fn expression(&mut self) -> Option<Expression> {
+        let mut operands = vec![self.unary()?];
+        while self.eat(&Token::Pipe) {
+            operands.push(self.unary()?);
+        }
+
+        Some(if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            Expression::BitwiseOr(operands)
+        })
+    }
+
+    fn unary(&mut self) -> Option<Expression> {
+        if !self.eat(&Token::Minus) {
+            return self.primary();
+        }
+
+        match self.unary()? {
+            Expression::Int(value) => Some(Expression::Int(-value)),
+            Expression::Float(value) => Some(Expression::Float(format!("-{value}"))),
+            _ => None,
+        }
+    }
+
+    fn primary(&mut self) -> Option<Expression> {
+        match self.next()? {
+            Token::Int(value) => Some(Expression::Int(value)),
+            Token::Float(value) => Some(Expression::Float(value)),
+            Token::String(value) => Some(Expression::String(value)),
+            Token::OpenBracket => Some(Expression::Array(self.array_items(&Token::CloseBracket)?)),
+            Token::OpenParenthesis => {
+                let expression = self.expression()?;
+                self.expect(&Token::CloseParenthesis)?;
+
+                Some(expression)
+            }
+            Token::Identifier(identifier) => self.identifier(identifier),
+            _ => None,
+        }
+    }
+
+    fn identifier(&mut self, identifier: String) -> Option<Expression> {
+        match identifier.to_ascii_lowercase().as_str() {
+            "null" => return Some(Expression::Null),
+            "true" => return Some(Expression::Bool(true)),
+            "false" => return Some(Expression::Bool(false)),
+            "array" if self.eat(&Token::OpenParenthesis) => {
+                return Some(Expression::Array(
+                    self.array_items(&Token::CloseParenthesis)?,
+                ));
+            }
+            "new" => {
+                let Some(Token::Identifier(class)) = self.next() else {
+                    return None;
+                };
+                let arguments = if self.eat(&Token::OpenParenthesis) {
+                    self.arguments()?
+                } else {
+                    Vec::new()
+                };
+
+                return Some(Expression::New { class, arguments });
+            }
+            _ => {}
+        }
+
+        if self.eat(&Token::DoubleColon) {
+            let Some(Token::Identifier(constant)) = self.next() else {
+                return None;
+            };
+
+            return Some(Expression::ClassConstant {
+                class: identifier.into(),
+                constant: constant.into(),
+            });
+        }
+
+        Some(Expression::Constant(identifier.into()))
+    }
+
+    fn arguments(&mut self) -> Option<Vec<Expression>> {
+        let mut arguments = Vec::<Expression>::new();
+        while !self.eat(&Token::CloseParenthesis) {
+            arguments.push(self.expression()?);
+            if !self.eat(&Token::Comma) {
+                self.expect(&Token::CloseParenthesis)?;
+                break;
+            }
+        }
+
+        Some(arguments)
+    }
+
+    fn array_items(&mut self, closing: &Token) -> Option<Vec<ArrayItem>> {
+        let mut items = Vec::<ArrayItem>::new();
+        while !self.eat(closing) {
+            let value = self.expression()?;
+            let item = if self.eat(&Token::DoubleArrow) {
+                ArrayItem {
+                    key: Some(value),
+                    value: self.expression()?,
+                }
+            } else {
+                ArrayItem { key: None, value }
+            };
+            items.push(item);
+
+            if !self.eat(&Token::Comma) {
+                self.expect(closing)?;
+                break;
+            }
+        }
+
+        Some(items)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ArrayItem, Expression};
+
+    #[rstest::rstest]
+    #[case("null", Expression::Null)]
+    #[case("NULL", Expression::Null)]
+    #[case("false", Expression::Bool(false))]
+    #[case("-1", Expression::Int(-1))]
+    #[case("0x1F", Expression::Int(31))]
+    #[case("0644", Expression::Int(420))]
+    #[case("1_000", Expression::Int(1000))]
+    #[case("1.5e-3", Expression::Float("1.5e-3".to_string()))]
+    #[case(r#""UTF-8""#, Expression::String("UTF-8".to_string()))]
+    #[case("'\\''", Expression::String("'".to_string()))]
+    #[case("PHP_INT_MAX", Expression::Constant("PHP_INT_MAX".into()))]
+    #[case(
+        "DateTimeInterface::ATOM",
+        Expression::ClassConstant { class: "DateTimeInterface".into(), constant: "ATOM".into() }
+    )]
+    #[case(
+        "ENT_QUOTES | ENT_SUBSTITUTE | ENT_HTML401",
+        Expression::BitwiseOr(vec![
+            Expression::Constant("ENT_QUOTES".into()),
+            Expression::Constant("ENT_SUBSTITUTE".into()),
+            Expression::Constant("ENT_HTML401".into()),
+        ])
+    )]
+    #[case("[]", Expression::Array(Vec::new()))]
+    #[case(
+        "array(\"a\" => 1, 2,)",
+        Expression::Array(vec![
+            ArrayItem { key: Some(Expression::String("a".to_string())), value: Expression::Int(1) },
+            ArrayItem { key: None, value: Expression::Int(2) },
+        ])
+    )]
+    #[case(
+        "new \\Random\\Engine\\Mt19937(42)",
+        Expression::New { class: "\\Random\\Engine\\Mt19937".to_string(), arguments: vec![Expression::Int(42)] }
+    )]
+    #[case("$foo", Expression::Raw("$foo".to_string()))]
+    #[case("1 +", Expression::Raw("1 +".to_string()))]
+    pub fn test_expression_parsing(#[case] source: &str, #[case] expected: Expression) {
+        assert_eq!(Expression::from(source), expected);
+    }
+
+    #[test]
+    pub fn test_expression_evaluation() {
+        let expression = Expression::from("JSON_HEX_TAG | JSON_HEX_AMP | 4");
+        let resolve = |name: &str| match name {
+            "JSON_HEX_TAG" => Some(1),
+            "JSON_HEX_AMP" => Some(2),
+            _ => None,
+        };
+
+        assert_eq!(expression.evaluate(&resolve), Some(7));
+        assert_eq!(
+            expression.constants(),
+            vec!["JSON_HEX_TAG".to_string(), "JSON_HEX_AMP".to_string()]
+        );
+        assert_eq!(Expression::from("UNKNOWN").evaluate(&resolve), None);
+        assert_eq!(expression.to_string(), "JSON_HEX_TAG | JSON_HEX_AMP | 4");
+    }
+}
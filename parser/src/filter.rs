@@ -0,0 +1,307 @@
+use std::fmt;
+
+use super::{
+    attribute::Attribute,
+    manual::{Entity, Manual},
+    r#type::TypeHint,
+    version::PhpVersion,
+};
+
+/// What a [`Filter`] requires of an entity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    /// `ext:mbstring`, documented by the extension
+    Extension(String),
+    /// `returns:string`, a function or method returning the type, or a union containing it
+    Returns(String),
+    /// `param:callable` or `param:$flags`, a function or method with a parameter of the type or
+    /// name
+    Parameter(String),
+    /// `deprecated:`, a deprecated function, method or class
+    Deprecated,
+    /// `since:8.0`, available from this version of PHP onwards, i.e. added in it or later
+    ///
+    /// Entities whose extension does not document their version match neither `since:` nor
+    /// `-since:`
+    Since(PhpVersion),
+}
+
+/// A condition of a [`SearchQuery`], excluding the entities matching it when `negated`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub condition: Condition,
+    pub negated: bool,
+}
+
+impl Filter {
+    const NEGATION: char = '-';
+
+    /// Parses `key:value` or `-key:value`, `None` for anything else
+    fn parse(word: &str) -> Option<Self> {
+        let (negated, word) = match word.strip_prefix(Self::NEGATION) {
+            Some(word) => (true, word),
+            None => (false, word),
+        };
+
+        // `-deprecated` reads better than `-deprecated:`
+        if negated && word.eq_ignore_ascii_case("deprecated") {
+            return Some(Self {
+                condition: Condition::Deprecated,
+                negated,
+            });
+        }
+
+        let (key, value) = word.split_once(':')?;
+        let condition = match (key.to_lowercase().as_str(), value) {
+            ("deprecated", "" | "true" | "yes") => Condition::Deprecated,
+            ("deprecated", "false" | "no") => {
+                return Some(Self {
+                    condition: Condition::Deprecated,
+                    negated: !negated,
+                });
+            }
+            (_, "") => return None,
+            ("ext", extension) => Condition::Extension(extension.to_string()),
+            ("returns", r#type) => Condition::Returns(r#type.to_string()),
+            ("param", parameter) => Condition::Parameter(parameter.to_string()),
+            ("since", version) => Condition::Since(version.parse().ok()?),
+            _ => return None,
+        };
+
+        Some(Self { condition, negated })
+    }
+
+    pub fn matches(&self, manual: &Manual, entity: Entity) -> bool {
+        let matches = match (&self.condition, entity) {
+            (Condition::Extension(extension), _) => manual
+                .extension(entity)
+                .is_some_and(|entity_extension| entity_extension.eq_ignore_ascii_case(extension)),
+            (Condition::Returns(r#type), Entity::Function(function) | Entity::Method(function)) => {
+                has_type(&function.return_type, r#type)
+            }
+            (
+                Condition::Parameter(parameter),
+                Entity::Function(function) | Entity::Method(function),
+            ) => match parameter.strip_prefix('$') {
+                Some(name) => function
+                    .arguments
+                    .iter()
+                    .any(|argument| argument.name.eq_ignore_ascii_case(name)),
+                None => function
+                    .arguments
+                    .iter()
+                    .any(|argument| has_type(&argument.r#type, parameter)),
+            },
+            (Condition::Deprecated, Entity::Function(function) | Entity::Method(function)) => {
+                function.deprecated
+            }
+            (Condition::Deprecated, Entity::Class(class)) => class
+                .attributes
+                .iter()
+                .any(|attribute| attribute.is(Attribute::DEPRECATED)),
+            (Condition::Since(version), _) => match manual.since(entity) {
+                Some(since) => since >= *version,
+                // Not known to be added before the version either
+                None => return false,
+            },
+            (Condition::Returns(_) | Condition::Parameter(_) | Condition::Deprecated, _) => false,
+        };
+
+        matches != self.negated
+    }
+}
+
+/// Whether the type hint is `r#type` or a union containing it, ignoring case and leading
+/// backslashes
+fn has_type(type_hint: &TypeHint, r#type: &str) -> bool {
+    let r#type = r#type.trim_start_matches('\\');
+
+    type_hint
+        .members()
+        .into_iter()
+        .any(|member| member.trim_start_matches('\\').eq_ignore_ascii_case(r#type))
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            write!(f, "{}", Self::NEGATION)?;
+        }
+
+        match &self.condition {
+            Condition::Extension(extension) => write!(f, "ext:{extension}"),
+            Condition::Returns(r#type) => write!(f, "returns:{type}"),
+            Condition::Parameter(parameter) => write!(f, "param:{parameter}"),
+            Condition::Deprecated => write!(f, "deprecated"),
+            Condition::Since(version) => write!(f, "since:{version}"),
+        }
+    }
+}
+
+/// A search of the manual, e.g. `ext:mbstring returns:string -deprecated str`: the filters every
+/// result must match and the remaining text, matched against the names of the entities
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub filters: Vec<Filter>,
+    pub text: String,
+}
+
+impl SearchQuery {
+    /// Words which are not valid filters, such as `foo:` or `since:php`, are kept in the text
+    pub fn parse(query: &str) -> Self {
+        let mut filters = Vec::new();
+        let mut words = Vec::new();
+        for word in query.split_whitespace() {
+            match Filter::parse(word) {
+                Some(filter) => filters.push(filter),
+                None => words.push(word),
+            }
+        }
+
+        Self {
+            filters,
+            text: words.join(" "),
+        }
+    }
+
+    /// Whether the entity matches all the filters
+    pub fn matches(&self, manual: &Manual, entity: Entity) -> bool {
+        self.filters
+            .iter()
+            .all(|filter| filter.matches(manual, entity))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::SearchQuery;
+    use crate::{
+        function::FunctionDefinition,
+        manual::{Entity, Manual, Page},
+        version::Availability,
+    };
+
+    fn function(name: &str, return_type: &str, parameters: &[&str]) -> Page {
+        parameters
+            .iter()
+            .fold(
+                FunctionDefinition::stub(name).returning(return_type),
+                |function, parameter| function.parameter(parameter),
+            )
+            .deprecated(name == "utf8_encode")
+            .page()
+    }
+
+    fn manual() -> Manual {
+        let mut manual = Manual::default();
+        for (path, page) in [
+            (
+                "reference/strings/functions/strlen.xml",
+                function("strlen", "int", &["string $string"]),
+            ),
+            (
+                "reference/strings/functions/str-contains.xml",
+                function(
+                    "str_contains",
+                    "bool",
+                    &["string $haystack", "string $needle"],
+                ),
+            ),
+            (
+                "reference/mbstring/functions/mb-substr.xml",
+                function(
+                    "mb_substr",
+                    "string",
+                    &[
+                        "string $string",
+                        "int $start",
+                        "?int $length",
+                        "?string $encoding",
+                    ],
+                ),
+            ),
+            (
+                "reference/strings/functions/utf8-encode.xml",
+                function("utf8_encode", "string", &["string $string"]),
+            ),
+            (
+                "reference/strings/functions/strpos.xml",
+                function(
+                    "strpos",
+                    "int|false",
+                    &["string $haystack", "string $needle", "int $offset"],
+                ),
+            ),
+            (
+                "reference/strings/versions.xml",
+                Page::Versions(
+                    [
+                        ("strlen", "PHP 4, PHP 5, PHP 7, PHP 8"),
+                        ("str_contains", "PHP 8"),
+                        ("utf8_encode", "PHP 4, PHP 5, PHP 7, PHP 8"),
+                        ("strpos", "PHP 4, PHP 5, PHP 7, PHP 8"),
+                    ]
+                    .into_iter()
+                    .map(|(name, from)| Availability {
+                        name: name.to_string(),
+                        from: from.to_string(),
+                    })
+                    .collect(),
+                ),
+            ),
+        ] {
+            manual.insert(PathBuf::from(path), page);
+        }
+
+        manual
+    }
+
+    #[rstest::rstest]
+    #[case("ext:mbstring returns:string str", &["ext:mbstring", "returns:string"], "str")]
+    #[case("since:8.0", &["since:8.0.0"], "")]
+    #[case("str -deprecated", &["-deprecated"], "str")]
+    #[case("deprecated: -ext:Strings", &["deprecated", "-ext:Strings"], "")]
+    #[case("deprecated:no", &["-deprecated"], "")]
+    #[case("since:php foo: ext:", &[], "since:php foo: ext:")]
+    #[case("array, callable -> array", &[], "array, callable -> array")]
+    pub fn test_query_parsing(#[case] query: &str, #[case] filters: &[&str], #[case] text: &str) {
+        let query = SearchQuery::parse(query);
+
+        assert_eq!(
+            query
+                .filters
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            filters
+        );
+        assert_eq!(query.text, text);
+    }
+
+    #[rstest::rstest]
+    #[case("ext:mbstring", &["mb_substr"])]
+    #[case("returns:string", &["mb_substr", "utf8_encode"])]
+    #[case("returns:int", &["strlen", "strpos"])]
+    #[case("returns:false -returns:int", &[])]
+    #[case("param:null", &["mb_substr"])]
+    #[case("param:$needle", &["str_contains", "strpos"])]
+    #[case("ext:strings -deprecated returns:string", &[])]
+    #[case("deprecated:", &["utf8_encode"])]
+    #[case("since:8.0", &["str_contains"])]
+    #[case("-since:8", &["strlen", "strpos", "utf8_encode"])]
+    #[case("-ext:strings -since:5", &[])]
+    pub fn test_filtering(#[case] query: &str, #[case] expected: &[&str]) {
+        let manual = manual();
+        let query = SearchQuery::parse(query);
+
+        let functions = manual
+            .functions()
+            .filter(|function| query.matches(&manual, Entity::Function(function)))
+            .map(|function| function.name.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(functions, expected);
+    }
+}
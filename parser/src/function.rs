@@ -2,34 +2,69 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
-use super::{text::TextNode, r#type::TypeHint};
+use super::{
+    attribute::Attribute, expression::Expression, name::FullyQualifiedName, text::TextNode,
+    r#type::TypeHint,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Function {
     Definition(FunctionDefinition),
-    Alias(String),
+    Alias(FunctionAlias),
 }
 
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Function::Definition(function_definition) => function_definition.fmt(f),
-            Function::Alias(string) => string.fmt(f),
+            Function::Alias(alias) => alias.fmt(f),
+        }
+    }
+}
+
+/// A function documented as another name of a function, e.g. `join` for `implode`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FunctionAlias {
+    pub name: FullyQualifiedName,
+    /// The function it is an alias of, `None` if its page does not link to it
+    pub target: Option<FullyQualifiedName>,
+}
+
+impl fmt::Display for FunctionAlias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(f, "{}: alias of {target}", self.name),
+            None => self.name.fmt(f),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct FunctionDefinition {
-    pub name: String,
+    pub name: FullyQualifiedName,
     pub short_description: String,
     pub return_type: TypeHint,
     pub arguments: Vec<Parameter>,
     pub description: Vec<TextNode>,
+    pub attributes: Vec<Attribute>,
+    /// Whether the function is marked `#[\Deprecated]` or its page warns that it is deprecated
+    pub deprecated: bool,
+    /// Functions its page links to in its "See Also" section
+    pub see_also: Vec<FullyQualifiedName>,
+}
+
+impl FunctionDefinition {
+    pub fn has_sensitive_parameters(&self) -> bool {
+        self.arguments.iter().any(Parameter::is_sensitive)
+    }
 }
 
 impl fmt::Display for FunctionDefinition {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for attribute in &self.attributes {
+            writeln!(f, "{attribute}")?;
+        }
+
         write!(f, "{}(", self.name)?;
         for (i, arg) in self.arguments.iter().enumerate() {
             write!(
@@ -58,11 +93,92 @@ impl fmt::Display for FunctionDefinition {
     }
 }
 
+/// The parts of a function page only needed to display it, parsed separately from its header
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionBody {
+    pub description: Vec<TextNode>,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Parameter {
     pub name: String,
     pub r#type: TypeHint,
     pub repeat: bool,
-    pub default_value: Option<String>,
-    pub attributes: Vec<String>,
+    pub default_value: Option<Expression>,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Parameter {
+    /// Whether the parameter is marked with `#[\SensitiveParameter]`, and thus redacted from stack traces
+    pub fn is_sensitive(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.is(Attribute::SENSITIVE_PARAMETER))
+    }
+}
+
+/// Builds functions for tests, e.g.
+/// `FunctionDefinition::stub("strpos").returning("int|false").parameter("string $haystack")`
+#[cfg(any(test, feature = "test-util"))]
+impl FunctionDefinition {
+    /// A function returning `mixed`, without parameters nor description
+    pub fn stub(name: &str) -> Self {
+        Self {
+            name: FullyQualifiedName::from(name),
+            short_description: String::new(),
+            return_type: TypeHint::default(),
+            arguments: Vec::new(),
+            description: Vec::new(),
+            attributes: Vec::new(),
+            deprecated: false,
+            see_also: Vec::new(),
+        }
+    }
+
+    pub fn described(mut self, short_description: &str) -> Self {
+        self.short_description = short_description.to_string();
+        self
+    }
+
+    /// See [`TypeHint::stub`]
+    pub fn returning(mut self, return_type: &str) -> Self {
+        self.return_type = TypeHint::stub(return_type);
+        self
+    }
+
+    /// Adds a parameter as written in a synopsis, e.g. `?callable $callback = null` or
+    /// `array ...$arrays`
+    pub fn parameter(mut self, parameter: &str) -> Self {
+        let mut words = parameter.split_whitespace();
+        let r#type = TypeHint::stub(words.next().unwrap_or_default());
+        let name = words.next().unwrap_or_default();
+
+        self.arguments.push(Parameter {
+            name: name.trim_start_matches(['.', '$']).to_string(),
+            r#type,
+            repeat: name.starts_with("..."),
+            // Skips the `=`
+            default_value: words.nth(1).map(Expression::from),
+            attributes: Vec::new(),
+        });
+        self
+    }
+
+    pub fn deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+
+    pub fn see_also(mut self, names: &[&str]) -> Self {
+        self.see_also = names
+            .iter()
+            .map(|name| FullyQualifiedName::from(*name))
+            .collect();
+        self
+    }
+
+    /// The function page of the function, without text to search
+    pub fn page(self) -> crate::manual::Page {
+        crate::manual::Page::Function(Function::Definition(self), Default::default())
+    }
 }
@@ -0,0 +1,33 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{text::TextNode, r#type::TypeHint};
+
+/// A `php.ini` directive, e.g. `memory_limit`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct IniDirective {
+    pub name: String,
+    pub r#type: Option<TypeHint>,
+    /// As written in the table of the directives, e.g. `"128M"`
+    pub default_value: Option<String>,
+    /// Where it can be set, e.g. `INI_ALL`
+    pub changeable: Option<String>,
+    pub description: Vec<TextNode>,
+}
+
+impl fmt::Display for IniDirective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if let Some(r#type) = &self.r#type {
+            write!(f, " ({type})")?;
+        }
+
+        if let Some(default_value) = &self.default_value {
+            write!(f, " = {default_value}")?;
+        }
+
+        Ok(())
+    }
+}
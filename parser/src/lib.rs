@@ -1,131 +1,437 @@
 use std::fmt::Debug;
 
-use function::{Function, FunctionDefinition};
-use libxml::{parser::XmlParseError, tree::NodeType};
+use attribute::Attribute;
+use chapter::Section;
+use class::{Class, ClassKind};
+use constant::Constant;
+use dom::{Element, Node};
+use expression::Expression;
+use function::{Function, FunctionAlias, FunctionBody, FunctionDefinition};
+use ini::IniDirective;
+use name::FullyQualifiedName;
+use search::{Document, Field};
 use text::TextNode;
 use r#type::TypeHint;
+use variable::{PredefinedVariable, VariableKey};
+use version::Availability;
 
+pub use backend::Backend;
+
+pub mod attribute;
+mod backend;
+pub mod chapter;
+pub mod class;
+pub mod constant;
+mod dom;
+pub mod expression;
+pub mod filter;
 pub mod function;
+pub mod ini;
+pub mod manual;
+pub mod name;
+pub mod search;
+pub mod signature;
+pub mod stream;
+pub mod suggestion;
+pub mod symbol;
+pub mod synonym;
 pub mod text;
 pub mod r#type;
+pub mod variable;
+pub mod version;
 
 #[derive(Debug, thiserror::Error)]
 pub enum XmlError {
+    #[cfg(feature = "backend-libxml")]
+    #[error(transparent)]
+    ParseError(libxml::parser::XmlParseError),
+    #[cfg(feature = "backend-pure")]
     #[error(transparent)]
-    ParseError(XmlParseError),
-    #[error("Could not register XML namespace ?")]
-    NamespaceRegistrationError,
-    #[error("Could not initialize the XML XPath Context")]
-    XPathInitializationError,
-    #[error("Could not evaluate an XPath expression")]
-    XPathEvaluationError,
+    ReadError(quick_xml::Error),
     #[error("Could not find the xml representation of the {0}")]
     MalformedXmlDefinition(&'static str),
     #[error("Could not read the xml file")]
     IOError(std::io::Error),
 }
 
+/// Revision of the parsed models, to bump whenever the output of the parser changes so that
+/// pages cached by consumers get invalidated
+pub const PARSER_VERSION: u32 = 9;
+
+/// DocBook elements rendered as a [`Section`]
+const SECTION_ELEMENTS: &[&str] = &[
+    "chapter",
+    "preface",
+    "appendix",
+    "sect1",
+    "sect2",
+    "sect3",
+    "sect4",
+    "sect5",
+    "section",
+    "simplesect",
+];
+
 #[derive(Default)]
 pub struct XmlParser {
-    parser: libxml::parser::Parser,
+    backend: Backend,
 }
 
 impl XmlParser {
-    fn get_string_from_xpath(
-        xpath: &libxml::xpath::Context,
-        path: &str,
-    ) -> Result<String, XmlError> {
-        xpath
-            .evaluate(path)
-            .map_err(|_| XmlError::XPathEvaluationError)
-            .map(|object| object.get_nodes_as_str().join(""))
+    pub fn new(backend: Backend) -> Self {
+        Self { backend }
     }
 
-    pub fn parse_function<Bytes: AsRef<[u8]>>(&self, content: Bytes) -> Result<Function, XmlError> {
-        let doc = self
-            .parser
-            .parse_string(content)
-            .map_err(XmlError::ParseError)?;
-
-        let xpath =
-            libxml::xpath::Context::new(&doc).map_err(|_| XmlError::XPathInitializationError)?;
-        xpath
-            .register_namespace("d", "http://docbook.org/ns/docbook")
-            .map_err(|_| XmlError::NamespaceRegistrationError)?;
-
-        let title = Self::get_string_from_xpath(&xpath, "//d:refentry/d:refnamediv/d:refname")?;
-        let short_description =
-            Self::get_string_from_xpath(&xpath, "//d:refentry/d:refnamediv/d:refpurpose")?;
-
-        let return_type = {
-            xpath
-                .evaluate(r#"//d:refentry/d:refsect1[@role="description"]/d:methodsynopsis/d:type"#)
-                .ok()
-                .and_then(|node| {
-                    if node.get_number_of_nodes() == 0 {
-                        None
-                    } else {
-                        Some(node)
+    /// Contents of the elements at `path` under every `<refentry>` of the document, joined
+    fn get_refentry_string(root: &Element, path: &str) -> String {
+        root.find_all("refentry")
+            .into_iter()
+            .flat_map(|refentry| refentry.select(path))
+            .map(Element::content)
+            .collect()
+    }
+
+    /// The `<refsect1 role="description">` of a `<refentry>` page
+    fn description_sections(root: &Element) -> Vec<&Element> {
+        if root.name != "refentry" {
+            return Vec::new();
+        }
+
+        root.select("refsect1[role=description]")
+    }
+
+    /// Text of the `<refsect1>`s of a `<refentry>` page with the given role, e.g. `parameters`
+    fn refentry_section_text(root: &Element, role: &str) -> String {
+        if root.name != "refentry" {
+            return String::new();
+        }
+
+        root.select(&format!("refsect1[role={role}]"))
+            .into_iter()
+            .map(Element::content)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Elements at `path` under the description of a `<refentry>` page
+    fn select_description<'a>(root: &'a Element, path: &str) -> Vec<&'a Element> {
+        Self::description_sections(root)
+            .into_iter()
+            .flat_map(|section| section.select(path))
+            .collect()
+    }
+
+    /// Flattens the children of the given block nodes (`<para>`, `<simpara>`...) into text nodes
+    fn parse_text_nodes(nodes: Vec<&Element>) -> Vec<TextNode> {
+        let mut text_nodes = Vec::<TextNode>::new();
+
+        for node in nodes.into_iter().flat_map(|node| &node.children) {
+            // Entities go through the same match as elements, as an element without content
+            let entity: Element;
+            let node = match node {
+                Node::Element(element) => element,
+                Node::Text(content) => {
+                    if !content.chars().all(|char| char.is_whitespace()) {
+                        text_nodes.push(TextNode::Text(
+                            content.split_whitespace().collect::<Vec<_>>().join(" "),
+                        ));
                     }
-                })
-                .map(|node| {
-                    node.get_nodes_as_vec()
-                        .into_iter()
-                        .next()
-                        .ok_or(XmlError::MalformedXmlDefinition("return type"))
-                })
-                .transpose()?
-                .map(TypeHint::from)
+                    continue;
+                }
+                Node::EntityReference(name) => {
+                    entity = Element {
+                        name: name.clone(),
+                        ..Default::default()
+                    };
+                    &entity
+                }
+                Node::Comment(_) => continue,
+            };
+
+            let content = node.content();
+            let text_node = match node.name.as_str() {
+                "function" => TextNode::Function(content.into()),
+                "constant" => TextNode::Constant(content.into()),
+                "parameter" => TextNode::Parameter(content),
+                "varname" => TextNode::Variable(content),
+                "classname" => TextNode::Classname(content.into()),
+                "interfacename" => TextNode::InterfaceName(content.into()),
+                "literal" => TextNode::Literal(content),
+                "filename" => TextNode::Filename(content),
+                "type" => TextNode::Type(TypeHint::from(node)),
+                "programlisting" => TextNode::Code(content),
+                "example" | "informalexample" => TextNode::Code(content),
+                "link" | "uri" => TextNode::Link(content),
+                "methodname" => TextNode::MethodName(content.into()),
+                "table" => TextNode::Table(content),
+                "xref" => TextNode::Xref(node.attribute("linkend").unwrap_or_default().to_string()),
+                "return.falseforfailure" => TextNode::Text("false on failure".to_string()),
+                // wtf ?
+                "return.success" => {
+                    TextNode::Text("Returns true on success or false on failure".to_string())
+                }
+                "emphasis"
+                    if node
+                        .attribute("role")
+                        .map(|role| role == "bold" || role == "strong")
+                        .unwrap_or_default() =>
+                {
+                    TextNode::BoldText(content)
+                }
+                "command" => TextNode::BoldText(content),
+                "emphasis" if node.attribute("role").is_none() => {
+                    TextNode::ItalicText(content)
+                }
+                // TODO: implement this (html equivalent of <ul>, with <li> being <listitem>)
+                "itemizedlist" | "simplelist" | "orderedlist" | "variablelist" => TextNode::Text(content),
+                // TODO: actually implement this (Like show full text on hover ?)
+                "acronym" | "abbrev" => TextNode::Text(content),
+                "style.oop" => TextNode::Subtitle("Object-oriented style".to_string()),
+                "style.procedural" => TextNode::Subtitle("Procedural style".to_string()),
+                "note" | "tip" | "important" => TextNode::Note(content),
+                "warning" | "caution" => TextNode::Warning(content),
+                "footnote" => TextNode::ItalicText(content),
+                "exceptionname" | "enumname" => TextNode::Classname(content.into()),
+                "option" | "envar" | "systemitem" | "replaceable" | "property" | "modifier"
+                | "computeroutput" | "optional" => TextNode::InlineCode(content),
+                "screen" => TextNode::Inset(content),
+                "tag" => TextNode::HtmlTag(content),
+                "php.ini" => TextNode::InlineCode("php.ini".to_string()),
+                "code" | "userinput" => TextNode::InlinePhpCode(content),
+                "quote" => TextNode::ItalicText(format!(r#""{content}""#)),
+                "superscript" => TextNode::ItalicText(format!("^{content}")),
+                // TODO: Find a solution one day maybe ? No clue if possible though
+                "subscript" => TextNode::ItalicText(format!("⋁{content}")),
+                "warn.undocumented.func" => TextNode::Warning(
+                    "This function is currently not documented; only its argument list is available.".to_string()
+                ),
+                // TODO: Handle correctly :pray:
+                // Example at doc-en/reference/stream/functions/stream-context-set-option.xml
+                "methodsynopsis" => TextNode::None,
+                "indexterm" => TextNode::None,
+
+                // e.g. `productname`, `citetitle` or `sgmltag` in chapters of the language reference
+                name => {
+                    tracing::debug!("Unhandled text node {name}, {:?}", node.attributes);
+
+                    let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+                    // Unknown entities, and elements without any text
+                    if text.is_empty() { TextNode::None } else { TextNode::Text(text) }
+                }
+            };
+
+            if !matches!(text_node, TextNode::None) {
+                text_nodes.push(text_node);
+            }
+        }
+
+        text_nodes
+    }
+
+    /// Parses a `<chapter>`, `<sect1>`, `<section>`... and its nested sections into blocks of text
+    fn parse_section(node: &Element) -> Section {
+        let mut title = String::new();
+        let mut paragraphs = Vec::<Vec<TextNode>>::new();
+        let mut sections = Vec::<Section>::new();
+
+        for child in node.child_elements() {
+            let block = match child.name.as_str() {
+                "title" => {
+                    title = child
+                        .content()
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    continue;
+                }
+                "info" if title.is_empty() => {
+                    if let Some(info_title) =
+                        child.child_elements().find(|child| child.name == "title")
+                    {
+                        title = info_title
+                            .content()
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                    }
+                    continue;
+                }
+                name if SECTION_ELEMENTS.contains(&name) => {
+                    sections.push(Self::parse_section(child));
+                    continue;
+                }
+                "para" | "simpara" | "formalpara" => Self::parse_text_nodes(vec![child]),
+                "example" | "informalexample" => child
+                    .child_elements()
+                    .flat_map(|child| match child.name.as_str() {
+                        "title" => vec![TextNode::Subtitle(child.content())],
+                        "programlisting" | "screen" => vec![TextNode::Code(child.content())],
+                        _ => Self::parse_text_nodes(vec![child]),
+                    })
+                    .collect(),
+                "programlisting" => vec![TextNode::Code(child.content())],
+                "screen" => vec![TextNode::Inset(child.content())],
+                "note" | "tip" | "important" => vec![TextNode::Note(child.content())],
+                "warning" | "caution" => vec![TextNode::Warning(child.content())],
+                "table" | "informaltable" => vec![TextNode::Table(child.content())],
+                "titleabbrev" | "indexterm" => continue,
+                _ => vec![TextNode::Text(
+                    child
+                        .content()
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )],
+            };
+
+            if !block.is_empty() {
+                paragraphs.push(block);
+            }
+        }
+
+        Section {
+            id: node.attribute("id").map(str::to_string),
+            title,
+            paragraphs,
+            sections,
+        }
+    }
+
+    /// Reads the document with the backend of the parser, returning its root element
+    fn parse_document<Bytes: AsRef<[u8]>>(&self, content: Bytes) -> Result<Element, XmlError> {
+        self.backend.parse(content.as_ref())
+    }
+
+    pub fn parse_function<Bytes: AsRef<[u8]>>(&self, content: Bytes) -> Result<Function, XmlError> {
+        let root = self.parse_document(content)?;
+
+        let mut function = Self::parse_function_header(&root)?;
+        if let Function::Definition(function_definition) = &mut function {
+            function_definition.description = Self::parse_function_body(&root).description;
+        }
+
+        tracing::info!("Parsed function {:?}", &function);
+
+        Ok(function)
+    }
+
+    /// Parses only what is needed to list and search a function page: its name, purpose and
+    /// synopsis. The description is left empty, see [`XmlParser::parse_body`]
+    pub fn parse_header<Bytes: AsRef<[u8]>>(&self, content: Bytes) -> Result<Function, XmlError> {
+        let root = self.parse_document(content)?;
+
+        Self::parse_function_header(&root)
+    }
+
+    /// Parses the header of a function page along with its plain text for full-text search,
+    /// which is kept apart from the header, reading the document once
+    pub fn parse_indexed_header<Bytes: AsRef<[u8]>>(
+        &self,
+        content: Bytes,
+    ) -> Result<(Function, Document), XmlError> {
+        let root = self.parse_document(content)?;
+
+        Ok((
+            Self::parse_function_header(&root)?,
+            Self::parse_function_text(&root),
+        ))
+    }
+
+    /// Parses the plain text of a function or method page, as indexed for full-text search
+    pub fn parse_text<Bytes: AsRef<[u8]>>(&self, content: Bytes) -> Result<Document, XmlError> {
+        let root = self.parse_document(content)?;
+
+        Ok(Self::parse_function_text(&root))
+    }
+
+    /// Parses a method page (`reference/random/random/randomizer/getint.xml`) like a function
+    /// page, along with its plain text for full-text search
+    ///
+    /// Returns `None` for pages which do not document a method, including constructors and
+    /// destructors, whose synopsis has no return type
+    pub fn parse_method<Bytes: AsRef<[u8]>>(
+        &self,
+        content: Bytes,
+    ) -> Result<Option<(FunctionDefinition, Document)>, XmlError> {
+        let root = self.parse_document(content)?;
+        if root.name != "refentry" {
+            return Ok(None);
+        }
+
+        let Function::Definition(method) = Self::parse_function_header(&root)? else {
+            return Ok(None);
         };
+        if !method.name.name.contains("::") {
+            return Ok(None);
+        }
+
+        tracing::info!("Parsed method {}", &method.name);
+
+        Ok(Some((method, Self::parse_function_text(&root))))
+    }
+
+    /// Parses the parts of a function page which are only needed to display it
+    pub fn parse_body<Bytes: AsRef<[u8]>>(&self, content: Bytes) -> Result<FunctionBody, XmlError> {
+        let root = self.parse_document(content)?;
+
+        Ok(Self::parse_function_body(&root))
+    }
+
+    fn parse_function_header(root: &Element) -> Result<Function, XmlError> {
+        let title = Self::get_refentry_string(root, "refnamediv/refname");
+        let short_description = Self::get_refentry_string(root, "refnamediv/refpurpose");
+
+        let return_type = root
+            .find_all("refentry")
+            .into_iter()
+            .flat_map(|refentry| refentry.select("refsect1[role=description]/methodsynopsis/type"))
+            .next()
+            .map(TypeHint::from);
 
         let return_type = match return_type {
             Some(return_type) => return_type,
+            // e.g. `<refpurpose>&Alias; <function>implode</function></refpurpose>`
             None => {
-                return Ok(Function::Alias(short_description));
+                return Ok(Function::Alias(FunctionAlias {
+                    name: FullyQualifiedName::from(title.as_str()),
+                    target: root
+                        .find_all("refentry")
+                        .into_iter()
+                        .flat_map(|refentry| refentry.select("refnamediv/refpurpose/function"))
+                        .next()
+                        .map(|function| FullyQualifiedName::from(function.content().as_str())),
+                }));
             }
         };
 
         let function_params = {
-            let function_param_nodes = xpath
-                .evaluate(
-                    r#"/d:refentry/d:refsect1[@role="description"]/d:methodsynopsis/d:methodparam"#,
-                )
-                .map_err(|_| XmlError::MalformedXmlDefinition("function parameters"))?
-                .get_nodes_as_vec();
+            let function_param_nodes = Self::select_description(root, "methodsynopsis/methodparam");
 
             let mut parameters = Vec::<function::Parameter>::new();
 
             for method in function_param_nodes {
                 let mut r#type = Option::<TypeHint>::None;
                 let mut name = Option::<String>::None;
-                let mut default_value = Option::<String>::None;
-                let mut attributes = Vec::<String>::new();
+                let mut default_value = Option::<Expression>::None;
+                let mut attributes = Vec::<Attribute>::new();
 
-                let repeat = method
-                    .get_attribute("rep")
-                    .map(|value| value.as_str() == "repeat")
-                    .unwrap_or_default();
+                let repeat = method.attribute("rep") == Some("repeat");
 
-                for child in method.get_child_elements() {
-                    match child.get_name().as_str() {
+                for child in method.child_elements() {
+                    match child.name.as_str() {
                         "type" => {
                             r#type = Some(TypeHint::from(child));
                         }
                         "parameter" => {
-                            name = Some(child.get_content());
+                            name = Some(child.content());
                         }
                         "initializer" => {
-                            default_value = Some(child.get_content());
+                            default_value = Some(Expression::from(child.content().as_str()));
                         }
-                        "modifier"
-                            if child
-                                .get_attribute("role")
-                                .map(|role| role.as_str() == "attribute")
-                                .unwrap_or_default() =>
-                        {
-                            attributes.push(child.get_content());
+                        "modifier" if child.attribute("role") == Some("attribute") => {
+                            attributes.push(Attribute::from(child.content().as_str()));
                         }
-                        name => todo!("Unhandled case for <methodparam><{name}>..."),
+                        name => tracing::debug!("Ignored <methodparam><{name}>"),
                     };
                 }
 
@@ -139,8 +445,8 @@ impl XmlParser {
                             attributes,
                         });
                     }
-                    (r#type, name) => {
-                        todo!("Unhandled case where either {type:?} or {name:?} is unset?");
+                    (_, None) => {
+                        return Err(XmlError::MalformedXmlDefinition("parameter name"));
                     }
                 }
             }
@@ -148,140 +454,730 @@ impl XmlParser {
             parameters
         };
 
+        let attributes = Self::select_description(root, "methodsynopsis/modifier[role=attribute]")
+            .into_iter()
+            .map(|modifier| Attribute::from(modifier.content().as_str()))
+            .collect::<Vec<_>>();
+
+        // e.g. `&warn.deprecated.function-7-2-0.removed-8-0-0;`
+        let deprecated = attributes
+            .iter()
+            .any(|attribute| attribute.is(Attribute::DEPRECATED))
+            || root
+                .entity_references()
+                .iter()
+                .any(|name| name.starts_with("warn.deprecated"));
+
+        let see_also = root
+            .select("refsect1[role=seealso]")
+            .into_iter()
+            .flat_map(|section| section.find_all("function"))
+            .map(|function| FullyQualifiedName::from(function.content().as_str()))
+            .collect();
+
+        Ok(Function::Definition(FunctionDefinition {
+            name: FullyQualifiedName::from(title.as_str()),
+            short_description,
+            return_type,
+            arguments: function_params,
+            description: Vec::new(),
+            attributes,
+            deprecated,
+            see_also,
+        }))
+    }
+
+    /// Plain text of a function page, as indexed for full-text search
+    fn parse_function_text(root: &Element) -> Document {
+        let mut document = Document::default();
+        document.push(
+            Field::Name,
+            &Self::get_refentry_string(root, "refnamediv/refname"),
+        );
+        document.push(
+            Field::Summary,
+            &Self::get_refentry_string(root, "refnamediv/refpurpose"),
+        );
+        document.push(
+            Field::Description,
+            &Self::select_description(root, "para")
+                .into_iter()
+                .chain(Self::select_description(root, "simpara"))
+                .map(Element::content)
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        document.push(
+            Field::Parameters,
+            &Self::refentry_section_text(root, "parameters"),
+        );
+        document.push(
+            Field::Examples,
+            &Self::refentry_section_text(root, "examples"),
+        );
+
+        document
+    }
+
+    fn parse_function_body(root: &Element) -> FunctionBody {
         let description = {
-            let mut description_nodes = xpath
-                .evaluate(r#"/d:refentry/d:refsect1[@role="description"]/d:para"#)
-                .map(|node| node.get_nodes_as_vec())
-                .unwrap_or_default();
+            let mut description_nodes = Self::select_description(root, "para");
 
             if description_nodes.is_empty() {
-                let mut simparagraphs = xpath
-                    .evaluate(r#"/d:refentry/d:refsect1[@role="description"]/d:simpara"#)
-                    .map(|node| node.get_nodes_as_vec())
+                description_nodes = Self::select_description(root, "simpara");
+            }
+
+            Self::parse_text_nodes(description_nodes)
+        };
+
+        FunctionBody { description }
+    }
+
+    /// Parses a predefined variable page (`language/predefined/variables/*.xml`)
+    pub fn parse_variable<Bytes: AsRef<[u8]>>(
+        &self,
+        content: Bytes,
+    ) -> Result<PredefinedVariable, XmlError> {
+        let root = self.parse_document(content)?;
+
+        let name = Self::get_refentry_string(&root, "refnamediv/refname");
+        if name.is_empty() {
+            return Err(XmlError::MalformedXmlDefinition("variable name"));
+        }
+
+        let short_description = Self::get_refentry_string(&root, "refnamediv/refpurpose");
+
+        let description_sections = Self::description_sections(&root);
+        let description = Self::parse_text_nodes(
+            description_sections
+                .iter()
+                .flat_map(|section| section.child_elements())
+                .filter(|element| element.name == "para" || element.name == "simpara")
+                .collect(),
+        );
+
+        let keys = description_sections
+            .into_iter()
+            .flat_map(|section| section.find_all("varlistentry"))
+            .filter_map(|entry| {
+                let mut name = Option::<String>::None;
+                let mut description = Vec::<TextNode>::new();
+
+                for child in entry.child_elements() {
+                    match child.name.as_str() {
+                        "term" => {
+                            name =
+                                Some(child.content().trim().trim_matches(['\'', '"']).to_string());
+                        }
+                        "listitem" => {
+                            description = Self::parse_text_nodes(child.child_elements().collect());
+                        }
+                        _ => {}
+                    }
+                }
+
+                Some(VariableKey {
+                    name: name?,
+                    description,
+                })
+            })
+            .collect();
+
+        let variable = PredefinedVariable {
+            name,
+            short_description,
+            description,
+            keys,
+        };
+
+        tracing::info!("Parsed variable {:?}", &variable);
+
+        Ok(variable)
+    }
+
+    /// Parses a page of the language reference or of the features chapter
+    ///
+    /// Returns `None` when the root element of the document is not a section
+    pub fn parse_chapter<Bytes: AsRef<[u8]>>(
+        &self,
+        content: Bytes,
+    ) -> Result<Option<Section>, XmlError> {
+        let root = self.parse_document(content)?;
+
+        if !SECTION_ELEMENTS.contains(&root.name.as_str()) {
+            return Ok(None);
+        }
+
+        let section = Self::parse_section(&root);
+
+        tracing::info!("Parsed chapter {:?}", &section.title);
+
+        Ok(Some(section))
+    }
+
+    /// Parses the `versions.xml` of an extension, listing the PHP versions its functions, classes
+    /// and methods are available in
+    pub fn parse_versions<Bytes: AsRef<[u8]>>(
+        &self,
+        content: Bytes,
+    ) -> Result<Vec<Availability>, XmlError> {
+        let root = self.parse_document(content)?;
+
+        Ok(root
+            .find_all("function")
+            .into_iter()
+            .filter_map(|function| {
+                Some(Availability {
+                    name: function.attribute("name")?.to_string(),
+                    from: function.attribute("from")?.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Parses the `constants.xml` of an extension, listing its predefined constants
+    pub fn parse_constants<Bytes: AsRef<[u8]>>(
+        &self,
+        content: Bytes,
+    ) -> Result<Vec<Constant>, XmlError> {
+        let root = self.parse_document(content)?;
+
+        // e.g. `<term><constant>PHP_ROUND_HALF_UP</constant> (<type>int</type>)</term>`
+        let listed = root
+            .find_all("varlistentry")
+            .into_iter()
+            .filter_map(|entry| {
+                let term = entry.select("term").into_iter().next()?;
+                let name = term.find_all("constant").into_iter().next()?.content();
+
+                Some(Constant {
+                    name: FullyQualifiedName::from(name.as_str()),
+                    r#type: term.find_all("type").into_iter().next().map(TypeHint::from),
+                    description: Self::parse_text_nodes(
+                        entry
+                            .select("listitem")
+                            .into_iter()
+                            .flat_map(Element::child_elements)
+                            .collect(),
+                    ),
+                })
+            });
+
+        // Some extensions list them in a table instead, the constant first and its description
+        // last
+        let tabulated = root.find_all("row").into_iter().filter_map(|row| {
+            let entries = row.select("entry");
+            let name = entries
+                .first()?
+                .find_all("constant")
+                .into_iter()
+                .next()?
+                .content();
+
+            Some(Constant {
+                name: FullyQualifiedName::from(name.as_str()),
+                r#type: None,
+                description: Self::parse_text_nodes(
+                    entries.into_iter().skip(1).last().into_iter().collect(),
+                ),
+            })
+        });
+
+        let constants = listed.chain(tabulated).collect::<Vec<_>>();
+
+        tracing::info!("Parsed {} constants", constants.len());
+
+        Ok(constants)
+    }
+
+    /// Parses the `ini.xml` of an extension, documenting its `php.ini` directives
+    pub fn parse_ini_directives<Bytes: AsRef<[u8]>>(
+        &self,
+        content: Bytes,
+    ) -> Result<Vec<IniDirective>, XmlError> {
+        let root = self.parse_document(content)?;
+
+        // The table of the directives gives their default value and where they can be set, by name
+        let summaries = root
+            .find_all("tbody")
+            .into_iter()
+            .flat_map(|tbody| tbody.select("row"))
+            .filter_map(|row| {
+                let entries = row.select("entry");
+                let column = |i: usize| {
+                    entries
+                        .get(i)
+                        .map(|entry| entry.content().trim().to_string())
+                        .filter(|content| !content.is_empty())
+                };
+
+                Some((column(0)?, (column(1), column(2))))
+            })
+            .collect::<Vec<_>>();
+
+        // e.g. `<term><parameter>memory_limit</parameter> <type>int</type></term>`
+        let directives = root
+            .find_all("varlistentry")
+            .into_iter()
+            .filter_map(|entry| {
+                let term = entry.select("term").into_iter().next()?;
+                let name = term
+                    .find_all("parameter")
+                    .into_iter()
+                    .next()?
+                    .content()
+                    .trim()
+                    .to_string();
+                let (default_value, changeable) = summaries
+                    .iter()
+                    .find(|(summarized, _)| *summarized == name)
+                    .map(|(_, summary)| summary.clone())
                     .unwrap_or_default();
 
-                description_nodes.append(&mut simparagraphs);
+                Some(IniDirective {
+                    r#type: term.find_all("type").into_iter().next().map(TypeHint::from),
+                    default_value,
+                    changeable,
+                    description: Self::parse_text_nodes(
+                        entry
+                            .select("listitem")
+                            .into_iter()
+                            .flat_map(Element::child_elements)
+                            .collect(),
+                    ),
+                    name,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        tracing::info!("Parsed {} ini directives", directives.len());
+
+        Ok(directives)
+    }
+
+    /// Parses a class, interface or trait page (`<reference role="class">`)
+    ///
+    /// Returns `None` when the document has no class synopsis
+    pub fn parse_class<Bytes: AsRef<[u8]>>(
+        &self,
+        content: Bytes,
+    ) -> Result<Option<Class>, XmlError> {
+        let root = self.parse_document(content)?;
+
+        let Some(synopsis) = root.find_all("classsynopsis").into_iter().next() else {
+            return Ok(None);
+        };
+
+        let explicit_kind = match synopsis.attribute("class") {
+            Some("interface") => Some(ClassKind::Interface),
+            Some("trait") => Some(ClassKind::Trait),
+            Some(_) => Some(ClassKind::Class),
+            None => None,
+        };
+
+        let mut kind = explicit_kind.unwrap_or_default();
+        let mut name = Option::<FullyQualifiedName>::None;
+        let mut modifiers = Vec::<String>::new();
+        let mut attributes = Vec::<Attribute>::new();
+        let mut extends = Option::<FullyQualifiedName>::None;
+        let mut implements = Vec::<FullyQualifiedName>::new();
+
+        // Older pages wrap the inheritance list in a <classsynopsisinfo>
+        let elements = synopsis.child_elements().flat_map(|element| {
+            if element.name == "classsynopsisinfo" && element.attribute("role").is_none() {
+                element.child_elements().collect()
+            } else {
+                vec![element]
             }
+        });
 
-            let mut description = Vec::<TextNode>::new();
+        for element in elements {
+            let element_name = element.name.as_str();
+            if element_name != "ooclass" && element_name != "oointerface" {
+                continue;
+            }
 
-            for node in description_nodes
-                .into_iter()
-                .flat_map(|node| node.get_child_nodes())
-            {
-                let content = node.get_content();
-                let text_node = match node.get_name().as_str() {
-                    "text" => if content.chars().all(|char| char.is_whitespace()) {
-                        TextNode::None
-                    } else {
-                        TextNode::Text(content.split_whitespace().collect::<Vec<_>>().join(" "))
-                    },
-                    "function" => TextNode::Function(content),
-                    "constant" => TextNode::Constant(content),
-                    "parameter" | "varname" => TextNode::Parameter(content),
-                    "classname" => TextNode::Classname(content),
-                    "interfacename" => TextNode::InterfaceName(content),
-                    "literal" => TextNode::Literal(content),
-                    "filename" => TextNode::Filename(content),
-                    "type" => TextNode::Type(TypeHint::from(node)),
-                    "programlisting" => TextNode::Code(content),
-                    "link" => TextNode::Link(content),
-                    "methodname" => TextNode::MethodName(content),
-                    "table" => TextNode::Table(content),
-                    "xref" => TextNode::Xref(node.get_attribute("linkend").unwrap_or_default()),
-                    "return.falseforfailure" => TextNode::Text("false on failure".to_string()),
-                    // wtf ?
-                    "return.success" => {
-                        TextNode::Text("Returns true on success or false on failure".to_string())
-                    }
-                    "emphasis"
-                        if node
-                            .get_attribute("role")
-                            .map(|role| &role == "bold" || &role == "strong")
-                            .unwrap_or_default() =>
-                    {
-                        TextNode::BoldText(content)
+            let mut identifier = Option::<FullyQualifiedName>::None;
+            let mut inheritance = Option::<String>::None;
+            let mut element_modifiers = Vec::<String>::new();
+
+            for child in element.child_elements() {
+                match child.name.as_str() {
+                    "classname" | "interfacename" => {
+                        identifier = Some(FullyQualifiedName::from(child.content().as_str()));
                     }
-                    "command" => TextNode::BoldText(content),
-                    "emphasis" if node.get_attribute("role").is_none() => {
-                        TextNode::ItalicText(content)
+                    "modifier" if child.attribute("role") == Some("attribute") => {
+                        attributes.push(Attribute::from(child.content().as_str()));
                     }
-                    // TODO: implement this (html equivalent of <ul>, with <li> being <listitem>)
-                    "itemizedlist" | "simplelist" => TextNode::Text(content),
-                    // TODO: actually implement this (Like show full text on hover ?)
-                    "acronym" | "abbrev" => TextNode::Text(content),
-                    "style.oop" | "style.procedural" => TextNode::Subtitle(content),
-                    "note" => TextNode::Note(content),
-                    "screen" => TextNode::Inset(content),
-                    "tag" => TextNode::HtmlTag(content),
-                    "php.ini" => TextNode::InlineCode("php.ini".to_string()),
-                    "code" | "userinput" => TextNode::InlinePhpCode(content),
-                    "quote" => TextNode::ItalicText(format!(r#""{content}""#)),
-                    "superscript" => TextNode::ItalicText(format!("^{content}")),
-                    // TODO: Find a solution one day maybe ? No clue if possible though
-                    "subscript" => TextNode::ItalicText(format!("⋁{content}")),
-                    "warn.undocumented.func" => TextNode::Warning(
-                        "This function is currently not documented; only its argument list is available.".to_string()
-                    ),
-                    // TODO: Handle correctly :pray:
-                    // Example at doc-en/reference/stream/functions/stream-context-set-option.xml
-                    "methodsynopsis" => TextNode::None,
-                    _ if node.get_type() == Some(NodeType::EntityRefNode) => {TextNode::None},
+                    "modifier" => match child.content().trim() {
+                        modifier @ ("extends" | "implements") => {
+                            inheritance = Some(modifier.to_string());
+                        }
+                        modifier => element_modifiers.push(modifier.to_string()),
+                    },
+                    _ => {}
+                }
+            }
 
-                    name => todo!("Unhandled text node {name}, {:?}", node.get_attributes()),
-                };
+            let Some(identifier) = identifier else {
+                continue;
+            };
+
+            match (&name, inheritance) {
+                (None, None) => {
+                    if element_name == "oointerface" && explicit_kind.is_none() {
+                        kind = ClassKind::Interface;
+                    }
 
-                if !matches!(text_node, TextNode::None) {
-                    description.push(text_node);
+                    name = Some(identifier);
+                    modifiers = element_modifiers;
                 }
+                // The class name is repeated at the start of the <classsynopsisinfo>
+                (Some(name), None) if *name == identifier => {}
+                _ if element_name == "ooclass" => extends = Some(identifier),
+                _ => implements.push(identifier),
             }
+        }
 
-            description
-        };
+        let name = name.ok_or(XmlError::MalformedXmlDefinition("class name"))?;
 
-        let function = FunctionDefinition {
-            name: title,
-            short_description,
-            return_type,
-            arguments: function_params,
+        let description = Self::parse_text_nodes(
+            root.find_all("partintro")
+                .into_iter()
+                .flat_map(|partintro| partintro.select("section"))
+                .filter(|section| {
+                    section
+                        .attribute("id")
+                        .is_some_and(|id| id.contains(".intro"))
+                })
+                .flat_map(Element::child_elements)
+                .filter(|element| element.name == "para" || element.name == "simpara")
+                .collect(),
+        );
+
+        let class = Class {
+            name,
+            kind,
+            modifiers,
+            attributes,
+            extends,
+            implements,
             description,
         };
 
-        tracing::info!("Parsed function {:?}", &function);
+        tracing::info!("Parsed class {:?}", &class);
 
-        Ok(Function::Definition(function))
+        Ok(Some(class))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
-    use super::XmlParser;
+    use super::{Backend, XmlParser, function::Function, search::Field, text::TextNode};
 
-    async fn do_test(file: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        let parser = XmlParser::default();
-        let function = parser.parse_function(tokio::fs::read(&file).await?)?;
-        let name = file
-            .components()
+    fn snapshot_name(file: &Path) -> String {
+        file.components()
             .map(|component| component.as_os_str().to_str().unwrap_or_default())
             .skip_while(|component| *component != ".data")
             .collect::<Vec<_>>()
-            .join("_");
+            .join("_")
+    }
+
+    async fn do_test(parser: &XmlParser, file: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        let content = tokio::fs::read(&file).await?;
+        let function = parser.parse_function(&content)?;
 
-        insta::assert_snapshot!(name, format!("{function:#?}"));
+        insta::assert_snapshot!(snapshot_name(&file), format!("{function:#?}"));
+
+        // Parsing the header then the body must give the same function
+        let mut lazy_function = parser.parse_header(&content)?;
+        if let Function::Definition(function_definition) = &mut lazy_function {
+            function_definition.description = parser.parse_body(&content)?.description;
+        }
+        assert_eq!(lazy_function, function);
 
         Ok(())
     }
 
+    // Every backend is checked against the same snapshots, since they must give identical models
+
     #[rstest::rstest]
     #[tokio::test]
     pub async fn smoke_test_function_parsing() -> Result<(), Box<dyn std::error::Error>> {
-        for file in glob::glob("../.data/**/functions/**/*.xml")? {
-            let file = file?;
-            do_test(file).await?
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+
+            for file in glob::glob("../.data/**/functions/**/*.xml")? {
+                let file = file?;
+                do_test(&parser, file).await?
+            }
+        }
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[tokio::test]
+    pub async fn smoke_test_class_parsing() -> Result<(), Box<dyn std::error::Error>> {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+
+            for file in glob::glob("../.data/reference/*/*.xml")? {
+                let file = file?;
+                if let Some(class) = parser.parse_class(tokio::fs::read(&file).await?)? {
+                    insta::assert_snapshot!(snapshot_name(&file), format!("{class:#?}"));
+                }
+            }
         }
 
         Ok(())
     }
+
+    #[rstest::rstest]
+    #[tokio::test]
+    pub async fn smoke_test_chapter_parsing() -> Result<(), Box<dyn std::error::Error>> {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+
+            for file in glob::glob("../.data/language/**/*.xml")? {
+                let file = file?;
+                if let Some(section) = parser.parse_chapter(tokio::fs::read(&file).await?)? {
+                    insta::assert_snapshot!(snapshot_name(&file), format!("{section:#?}"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[tokio::test]
+    pub async fn smoke_test_variable_parsing() -> Result<(), Box<dyn std::error::Error>> {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+
+            for file in glob::glob("../.data/language/predefined/variables/*.xml")? {
+                let file = file?;
+                let variable = parser.parse_variable(tokio::fs::read(&file).await?)?;
+
+                insta::assert_snapshot!(snapshot_name(&file), format!("{variable:#?}"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fixture(name: &str) -> Vec<u8> {
+        std::fs::read(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("fixtures")
+                .join(name),
+        )
+        .expect("fixtures are checked in")
+    }
+
+    #[rstest::rstest]
+    pub fn test_function_fixture() {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+            let Function::Definition(function) = parser
+                .parse_function(fixture("reference/strings/functions/str-contains.xml"))
+                .unwrap()
+            else {
+                panic!("str_contains is not an alias");
+            };
+
+            assert_eq!(function.name.to_string(), "str_contains");
+            let text = function.description.iter().collect::<Vec<_>>();
+            assert!(text.contains(&&TextNode::Text("false on failure".to_string())));
+            assert!(text.contains(&&TextNode::Subtitle("Procedural style".to_string())));
+            assert!(
+                !text
+                    .iter()
+                    .any(|node| matches!(node, TextNode::Text(text) if text.contains("TODO")))
+            );
+        }
+    }
+
+    #[rstest::rstest]
+    pub fn test_chapter_fixture() {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+            let chapter = parser
+                .parse_chapter(fixture("language/basic-syntax.xml"))
+                .unwrap()
+                .unwrap();
+
+            let text = format!("{chapter:?}");
+            assert!(text.contains(r#"InlineCode("php.ini")"#));
+            assert!(text.contains("This function is currently not documented"));
+            assert!(!text.contains("entity defined by the build"));
+        }
+    }
+
+    #[rstest::rstest]
+    pub fn test_variable_fixture() {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+            let variable = parser
+                .parse_variable(fixture("language/predefined/variables/server.xml"))
+                .unwrap();
+
+            let text = format!("{variable:?}");
+            assert!(text.contains("Returns true on success or false on failure"));
+            assert!(!text.contains("wording of the original page"));
+        }
+    }
+
+    #[rstest::rstest]
+    pub fn test_method_fixture() {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+            let (method, document) = parser
+                .parse_method(fixture("reference/random/random/randomizer/getint.xml"))
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(method.name.to_string(), "Random\\Randomizer::getInt");
+            assert_eq!(
+                method.to_string(),
+                "Random\\Randomizer::getInt(int $min, int $max): int;"
+            );
+            assert!(
+                document
+                    .fields
+                    .iter()
+                    .any(|(field, text)| *field == Field::Description
+                        && text.contains("uniformly selected integer"))
+            );
+
+            let constructor = parser
+                .parse_method(fixture("reference/random/random/randomizer/construct.xml"))
+                .unwrap();
+            assert!(constructor.is_none());
+            assert!(
+                parser
+                    .parse_method(fixture("language/basic-syntax.xml"))
+                    .unwrap()
+                    .is_none()
+            );
+        }
+    }
+
+    #[rstest::rstest]
+    pub fn test_constants_fixture() {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+            let constants = parser
+                .parse_constants(fixture("reference/strings/constants.xml"))
+                .unwrap();
+
+            assert_eq!(
+                constants
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                [
+                    "CRYPT_SALT_LENGTH (int)",
+                    "HTML_SPECIALCHARS (int)",
+                    "LC_CTYPE"
+                ]
+            );
+            assert!(format!("{:?}", constants[0].description).contains("The length of the salt"));
+            assert!(format!("{:?}", constants[2].description).contains("Character classification"));
+        }
+    }
+
+    #[rstest::rstest]
+    pub fn test_ini_directives_fixture() {
+        for backend in Backend::ALL {
+            let parser = XmlParser::new(*backend);
+            let directives = parser
+                .parse_ini_directives(fixture("reference/info/ini.xml"))
+                .unwrap();
+
+            assert_eq!(
+                directives
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                [
+                    r#"memory_limit (int) = "128M""#,
+                    r#"max_execution_time (int) = "30""#
+                ]
+            );
+            assert_eq!(directives[0].changeable.as_deref(), Some("INI_ALL"));
+            assert!(
+                format!("{:?}", directives[0].description).contains("maximum amount of memory")
+            );
+        }
+    }
+
+    #[cfg(all(feature = "backend-libxml", feature = "backend-pure"))]
+    #[rstest::rstest]
+    #[case::function("reference/strings/functions/str-contains.xml")]
+    #[case::alias("reference/strings/functions/join.xml")]
+    pub fn test_backends_parse_functions_alike(#[case] name: &str) {
+        let [libxml, pure] = [Backend::Libxml, Backend::Pure].map(XmlParser::new);
+
+        assert_eq!(
+            libxml.parse_function(fixture(name)).unwrap(),
+            pure.parse_function(fixture(name)).unwrap()
+        );
+        assert_eq!(
+            libxml.parse_header(fixture(name)).unwrap(),
+            pure.parse_header(fixture(name)).unwrap()
+        );
+    }
+
+    #[cfg(all(feature = "backend-libxml", feature = "backend-pure"))]
+    #[rstest::rstest]
+    pub fn test_backends_parse_pages_alike() {
+        let [libxml, pure] = [Backend::Libxml, Backend::Pure].map(XmlParser::new);
+
+        assert_eq!(
+            libxml
+                .parse_class(fixture("reference/random/random.randomizer.xml"))
+                .unwrap(),
+            pure.parse_class(fixture("reference/random/random.randomizer.xml"))
+                .unwrap()
+        );
+        assert_eq!(
+            libxml
+                .parse_chapter(fixture("language/basic-syntax.xml"))
+                .unwrap(),
+            pure.parse_chapter(fixture("language/basic-syntax.xml"))
+                .unwrap()
+        );
+        assert_eq!(
+            libxml
+                .parse_variable(fixture("language/predefined/variables/server.xml"))
+                .unwrap(),
+            pure.parse_variable(fixture("language/predefined/variables/server.xml"))
+                .unwrap()
+        );
+        assert_eq!(
+            libxml
+                .parse_method(fixture("reference/random/random/randomizer/getint.xml"))
+                .unwrap(),
+            pure.parse_method(fixture("reference/random/random/randomizer/getint.xml"))
+                .unwrap()
+        );
+        assert_eq!(
+            libxml
+                .parse_constants(fixture("reference/strings/constants.xml"))
+                .unwrap(),
+            pure.parse_constants(fixture("reference/strings/constants.xml"))
+                .unwrap()
+        );
+        assert_eq!(
+            libxml
+                .parse_ini_directives(fixture("reference/info/ini.xml"))
+                .unwrap(),
+            pure.parse_ini_directives(fixture("reference/info/ini.xml"))
+                .unwrap()
+        );
+    }
 }
@@ -0,0 +1,1001 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    num::NonZeroUsize,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    XmlError, XmlParser,
+    chapter::Section,
+    class::Class,
+    constant::Constant,
+    function::{Function, FunctionAlias, FunctionBody, FunctionDefinition},
+    ini::IniDirective,
+    name::FullyQualifiedName,
+    search::{Document, Query, Terms, TextIndex},
+    stream::{Queue, parse_queue},
+    suggestion::did_you_mean,
+    variable::PredefinedVariable,
+    version::{Availability, PhpVersion},
+};
+
+/// The kind of page a source file is expected to contain, based on its location
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageKind {
+    Function,
+    Chapter,
+    Variable,
+    Class,
+    /// The `versions.xml` of an extension
+    Versions,
+    Method,
+    /// The `constants.xml` of an extension
+    Constants,
+    /// The `ini.xml` of an extension
+    IniDirectives,
+}
+
+/// Supported source files, relative to the root of a documentation checkout
+///
+/// Patterns overlap, the first one to match a file decides its kind
+const PATTERNS: &[(PageKind, &str)] = &[
+    (PageKind::Function, "**/functions/**/*.xml"),
+    (PageKind::Variable, "language/predefined/variables/*.xml"),
+    (PageKind::Chapter, "language/**/*.xml"),
+    (PageKind::Chapter, "features/**/*.xml"),
+    (PageKind::Versions, "reference/*/versions.xml"),
+    (PageKind::Constants, "reference/*/constants.xml"),
+    (PageKind::IniDirectives, "reference/*/ini.xml"),
+    (PageKind::Class, "reference/*/*.xml"),
+    (PageKind::Method, "reference/*/*/**/*.xml"),
+];
+
+impl PageKind {
+    /// Finds every supported source file of a documentation checkout, along with its kind
+    pub fn discover(root: &Path) -> Result<Vec<(PageKind, PathBuf)>, glob::PatternError> {
+        let mut files = Vec::<(PageKind, PathBuf)>::new();
+        let mut seen_paths = HashSet::<PathBuf>::new();
+
+        for (kind, pattern) in PATTERNS {
+            for path in glob::glob(&root.join(pattern).to_string_lossy())?.flatten() {
+                if seen_paths.insert(path.clone()) {
+                    files.push((*kind, path));
+                }
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// The kind of the source file at `path`, `None` if it is not a supported file of the
+    /// documentation checkout at `root`
+    pub fn of(root: &Path, path: &Path) -> Option<PageKind> {
+        let path = path.strip_prefix(root).ok()?;
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+
+        PATTERNS
+            .iter()
+            .find(|(_, pattern)| {
+                glob::Pattern::new(pattern)
+                    .is_ok_and(|pattern| pattern.matches_path_with(path, options))
+            })
+            .map(|(kind, _)| *kind)
+    }
+}
+
+/// A parsed documentation page, only the header of function pages is parsed upfront
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Page {
+    /// The header of the function, along with the terms of its page for full-text search
+    Function(Function, Terms),
+    /// `None` for files of the language reference which are not a section
+    Chapter(Option<Section>),
+    Variable(PredefinedVariable),
+    /// `None` for files of an extension which are not a class page (`book.xml`, `setup.xml`...)
+    Class(Option<Class>),
+    Versions(Vec<Availability>),
+    /// `None` for files of a class directory which are not a method page, see
+    /// [`XmlParser::parse_method`]
+    Method(Option<(FunctionDefinition, Terms)>),
+    Constants(Vec<Constant>),
+    IniDirectives(Vec<IniDirective>),
+}
+
+#[derive(Debug)]
+pub enum PageError {
+    Xml(XmlError),
+    /// The parser panicked on the page, with the message of the panic
+    Panicked(String),
+}
+
+impl fmt::Display for PageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PageError::Xml(error) => error.fmt(f),
+            PageError::Panicked(message) => write!(f, "The parser panicked: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for PageError {}
+
+/// Parses the page at `path`, expecting it to be of the given kind
+pub fn parse_page(xml_parser: &XmlParser, kind: PageKind, path: &Path) -> Result<Page, PageError> {
+    parse_file(path, |content| {
+        Ok(match kind {
+            PageKind::Function => {
+                let (function, document) = xml_parser.parse_indexed_header(content)?;
+
+                Page::Function(function, Terms::from(&document))
+            }
+            PageKind::Chapter => Page::Chapter(xml_parser.parse_chapter(content)?),
+            PageKind::Variable => Page::Variable(xml_parser.parse_variable(content)?),
+            PageKind::Class => Page::Class(xml_parser.parse_class(content)?),
+            PageKind::Versions => Page::Versions(xml_parser.parse_versions(content)?),
+            PageKind::Method => Page::Method(
+                xml_parser
+                    .parse_method(content)?
+                    .map(|(method, document)| (method, Terms::from(&document))),
+            ),
+            PageKind::Constants => Page::Constants(xml_parser.parse_constants(content)?),
+            PageKind::IniDirectives => {
+                Page::IniDirectives(xml_parser.parse_ini_directives(content)?)
+            }
+        })
+    })
+}
+
+/// Parses the body of the function page at `path`, whose header was loaded with [`parse_page`]
+pub fn parse_function_body(xml_parser: &XmlParser, path: &Path) -> Result<FunctionBody, PageError> {
+    parse_file(path, |content| xml_parser.parse_body(content))
+}
+
+/// Parses the plain text of the function or method page at `path`, as indexed for full-text
+/// search, to build the [`Snippet`](crate::search::Snippet) of a [`TextHit`] once displayed
+pub fn parse_page_text(xml_parser: &XmlParser, path: &Path) -> Result<Document, PageError> {
+    parse_file(path, |content| xml_parser.parse_text(content))
+}
+
+fn parse_file<T>(
+    path: &Path,
+    parse: impl FnOnce(Vec<u8>) -> Result<T, XmlError>,
+) -> Result<T, PageError> {
+    let content = std::fs::read(path).map_err(XmlError::IOError);
+
+    content.and_then(parse).map_err(PageError::Xml)
+}
+
+/// The extension a source file documents, e.g. `strings` for
+/// `reference/strings/functions/strlen.xml`
+pub fn extension_of(path: &Path) -> Option<&str> {
+    let mut components = path.components();
+    components.find(|component| *component == Component::Normal("reference".as_ref()))?;
+
+    components.next()?.as_os_str().to_str()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ManualOptions {
+    /// Number of files parsed in parallel
+    pub jobs: NonZeroUsize,
+}
+
+impl Default for ManualOptions {
+    fn default() -> Self {
+        Self {
+            jobs: std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+        }
+    }
+}
+
+/// Anything documented by the manual
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entity<'a> {
+    Function(&'a FunctionDefinition),
+    Class(&'a Class),
+    Chapter(&'a Section),
+    Variable(&'a PredefinedVariable),
+    Method(&'a FunctionDefinition),
+    Constant(&'a Constant),
+    IniDirective(&'a IniDirective),
+}
+
+/// An entity matching a full-text search, see [`Manual::search_text`]
+#[derive(Debug, Clone)]
+pub struct TextHit<'a> {
+    pub entity: Entity<'a>,
+    /// BM25 relevance, only comparable between hits of the same search
+    pub score: f32,
+    /// The file of the page, see [`parse_page_text`]
+    pub path: &'a Path,
+}
+
+/// What a source file added to a [`Manual`], to take it out once the file changes
+#[derive(Debug)]
+enum PageEntry {
+    Function(FullyQualifiedName),
+    Class(FullyQualifiedName),
+    Chapter,
+    Variable(String),
+    /// The keys of the versions
+    Versions(Vec<String>),
+    Alias(FullyQualifiedName),
+    Method(FullyQualifiedName),
+    Constants(Vec<FullyQualifiedName>),
+    /// The names of the directives
+    IniDirectives(Vec<String>),
+}
+
+/// Every page of a documentation checkout, with lookups by name and by extension
+#[derive(Debug, Default)]
+pub struct Manual {
+    functions: BTreeMap<FullyQualifiedName, FunctionDefinition>,
+    classes: BTreeMap<FullyQualifiedName, Class>,
+    /// By source file, which orders them as the manual does rather than by title
+    chapters: BTreeMap<PathBuf, Section>,
+    variables: BTreeSet<PredefinedVariable>,
+    methods: BTreeMap<FullyQualifiedName, FunctionDefinition>,
+    /// Constants are case-sensitive, they are looked up by their exact name
+    constants: BTreeMap<FullyQualifiedName, Constant>,
+    ini_directives: BTreeMap<String, IniDirective>,
+    /// The function each alias is another name of
+    aliases: BTreeMap<FullyQualifiedName, FullyQualifiedName>,
+    /// Lowercased fully qualified names, as PHP names are case-insensitive
+    function_names: HashMap<String, FullyQualifiedName>,
+    class_names: HashMap<String, FullyQualifiedName>,
+    alias_names: HashMap<String, FullyQualifiedName>,
+    method_names: HashMap<String, FullyQualifiedName>,
+    function_sources: HashMap<FullyQualifiedName, PathBuf>,
+    class_sources: HashMap<FullyQualifiedName, PathBuf>,
+    method_sources: HashMap<FullyQualifiedName, PathBuf>,
+    constant_sources: HashMap<FullyQualifiedName, PathBuf>,
+    ini_directive_sources: HashMap<String, PathBuf>,
+    pages: HashMap<PathBuf, PageEntry>,
+    /// First version of PHP each function, class and method is available in, by lowercased name
+    versions: HashMap<String, PhpVersion>,
+    /// Only function and method pages are indexed so far
+    text_index: TextIndex<PathBuf>,
+    failures: Vec<(PathBuf, PageError)>,
+    /// Bumped by every change, see [`Manual::revision`]
+    revision: u64,
+}
+
+impl Manual {
+    /// Parses every supported page of the documentation checkout at `root`
+    ///
+    /// Pages which could not be parsed are listed by [`Manual::failures`]
+    pub fn load(root: &Path, options: ManualOptions) -> Result<Self, glob::PatternError> {
+        let queue = Queue::new(PageKind::discover(root)?.into_iter());
+
+        let results = std::thread::scope(|scope| {
+            let workers = (0..options.jobs.get())
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = Vec::<(PathBuf, Result<Page, PageError>)>::new();
+                        parse_queue(&queue, |path, result| {
+                            results.push((path, result));
+                            true
+                        });
+
+                        results
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            // Panics of the parser are caught per file by `parse_queue`, anything else is a bug
+            workers
+                .into_iter()
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut manual = Self::default();
+        for (path, result) in results {
+            match result {
+                Ok(page) => manual.insert(path, page),
+                Err(error) => manual.failures.push((path, error)),
+            }
+        }
+
+        Ok(manual)
+    }
+
+    /// Adds a page parsed from the file at `path`, replacing any page with the same name and the
+    /// page previously parsed from that file
+    pub fn insert(&mut self, path: PathBuf, page: Page) {
+        self.remove(&path);
+
+        let entry = match page {
+            Page::Function(Function::Definition(function), terms) => {
+                self.function_names.insert(
+                    function.name.to_string().to_lowercase(),
+                    function.name.clone(),
+                );
+                self.function_sources
+                    .insert(function.name.clone(), path.clone());
+                self.text_index.insert(path.clone(), terms);
+                let name = function.name.clone();
+                self.functions.insert(function.name.clone(), function);
+
+                PageEntry::Function(name)
+            }
+            Page::Class(Some(class)) => {
+                self.class_names
+                    .insert(class.name.to_string().to_lowercase(), class.name.clone());
+                self.class_sources.insert(class.name.clone(), path.clone());
+                let name = class.name.clone();
+                self.classes.insert(class.name.clone(), class);
+
+                PageEntry::Class(name)
+            }
+            Page::Chapter(Some(section)) => {
+                self.chapters.insert(path.clone(), section);
+
+                PageEntry::Chapter
+            }
+            Page::Variable(variable) => {
+                let entry = PageEntry::Variable(variable.name.clone());
+                self.variables.insert(variable);
+
+                entry
+            }
+            Page::Versions(availabilities) => {
+                let mut keys = Vec::<String>::new();
+                for availability in availabilities {
+                    if let Some(since) = availability.since() {
+                        let key = Self::key(&availability.name);
+                        self.versions.insert(key.clone(), since);
+                        keys.push(key);
+                    }
+                }
+
+                PageEntry::Versions(keys)
+            }
+            Page::Function(
+                Function::Alias(FunctionAlias {
+                    name,
+                    target: Some(target),
+                }),
+                _,
+            ) => {
+                self.alias_names
+                    .insert(name.to_string().to_lowercase(), name.clone());
+                self.aliases.insert(name.clone(), target);
+
+                PageEntry::Alias(name)
+            }
+            Page::Method(Some((method, terms))) => {
+                self.method_names
+                    .insert(method.name.to_string().to_lowercase(), method.name.clone());
+                self.method_sources
+                    .insert(method.name.clone(), path.clone());
+                self.text_index.insert(path.clone(), terms);
+                let name = method.name.clone();
+                self.methods.insert(method.name.clone(), method);
+
+                PageEntry::Method(name)
+            }
+            Page::Constants(constants) => {
+                let mut names = Vec::<FullyQualifiedName>::new();
+                for constant in constants {
+                    self.constant_sources
+                        .insert(constant.name.clone(), path.clone());
+                    names.push(constant.name.clone());
+                    self.constants.insert(constant.name.clone(), constant);
+                }
+
+                PageEntry::Constants(names)
+            }
+            Page::IniDirectives(directives) => {
+                let mut names = Vec::<String>::new();
+                for directive in directives {
+                    self.ini_directive_sources
+                        .insert(directive.name.clone(), path.clone());
+                    names.push(directive.name.clone());
+                    self.ini_directives
+                        .insert(directive.name.clone(), directive);
+                }
+
+                PageEntry::IniDirectives(names)
+            }
+            Page::Function(Function::Alias(FunctionAlias { target: None, .. }), _)
+            | Page::Chapter(None)
+            | Page::Class(None)
+            | Page::Method(None) => {
+                return;
+            }
+        };
+
+        self.pages.insert(path, entry);
+    }
+
+    /// Removes the page parsed from the file at `path`, e.g. once the file is deleted
+    pub fn remove(&mut self, path: &Path) {
+        self.revision += 1;
+        self.text_index.remove(path);
+        let Some(entry) = self.pages.remove(path) else {
+            return;
+        };
+
+        match entry {
+            // The name may have been taken over by another file since
+            PageEntry::Function(name) => {
+                if self
+                    .function_sources
+                    .get(&name)
+                    .is_some_and(|source| source == path)
+                {
+                    self.function_names.remove(&name.to_string().to_lowercase());
+                    self.function_sources.remove(&name);
+                    self.functions.remove(&name);
+                }
+            }
+            PageEntry::Class(name) => {
+                if self
+                    .class_sources
+                    .get(&name)
+                    .is_some_and(|source| source == path)
+                {
+                    self.class_names.remove(&name.to_string().to_lowercase());
+                    self.class_sources.remove(&name);
+                    self.classes.remove(&name);
+                }
+            }
+            PageEntry::Chapter => {
+                self.chapters.remove(path);
+            }
+            PageEntry::Variable(name) => self.variables.retain(|variable| variable.name != name),
+            PageEntry::Versions(keys) => {
+                for key in keys {
+                    self.versions.remove(&key);
+                }
+            }
+            PageEntry::Alias(name) => {
+                self.alias_names.remove(&name.to_string().to_lowercase());
+                self.aliases.remove(&name);
+            }
+            PageEntry::Method(name) => {
+                if self
+                    .method_sources
+                    .get(&name)
+                    .is_some_and(|source| source == path)
+                {
+                    self.method_names.remove(&name.to_string().to_lowercase());
+                    self.method_sources.remove(&name);
+                    self.methods.remove(&name);
+                }
+            }
+            PageEntry::Constants(names) => {
+                for name in names {
+                    if self
+                        .constant_sources
+                        .get(&name)
+                        .is_some_and(|source| source == path)
+                    {
+                        self.constant_sources.remove(&name);
+                        self.constants.remove(&name);
+                    }
+                }
+            }
+            PageEntry::IniDirectives(names) => {
+                for name in names {
+                    if self
+                        .ini_directive_sources
+                        .get(&name)
+                        .is_some_and(|source| source == path)
+                    {
+                        self.ini_directive_sources.remove(&name);
+                        self.ini_directives.remove(&name);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Looks a function up by its fully qualified name, case-insensitively
+    ///
+    /// Aliases resolve to the function they are another name of, e.g. `join` to `implode`
+    pub fn function(&self, name: &str) -> Option<&FunctionDefinition> {
+        let key = Self::key(name);
+        let name = match self.function_names.get(&key) {
+            Some(name) => name,
+            None => {
+                let alias = self.alias_names.get(&key)?;
+                self.function_names
+                    .get(&Self::key(&self.aliases.get(alias)?.to_string()))?
+            }
+        };
+
+        self.functions.get(name)
+    }
+
+    /// Looks a class, interface or trait up by its fully qualified name, case-insensitively
+    pub fn class(&self, name: &str) -> Option<&Class> {
+        let name = self.class_names.get(&Self::key(name))?;
+
+        self.classes.get(name)
+    }
+
+    /// Looks a method up by its fully qualified name, e.g. `Random\Randomizer::getInt`,
+    /// case-insensitively
+    pub fn method(&self, name: &str) -> Option<&FunctionDefinition> {
+        let name = self.method_names.get(&Self::key(name))?;
+
+        self.methods.get(name)
+    }
+
+    /// Looks a predefined constant up by its exact fully qualified name
+    pub fn constant(&self, name: &str) -> Option<&Constant> {
+        self.constants.get(&FullyQualifiedName::from(name))
+    }
+
+    /// Looks a `php.ini` directive up by its exact name, e.g. `memory_limit`
+    pub fn ini_directive(&self, name: &str) -> Option<&IniDirective> {
+        self.ini_directives.get(name.trim())
+    }
+
+    /// Functions, classes and methods named closest to `name`, for names which are not
+    /// documented, e.g. `str_contains` for `str_contians`
+    pub fn did_you_mean(&self, name: &str, limit: usize) -> Vec<Entity<'_>> {
+        let candidates = self
+            .functions()
+            .map(|function| (function.name.to_string(), Entity::Function(function)))
+            .chain(
+                self.classes()
+                    .map(|class| (class.name.to_string(), Entity::Class(class))),
+            )
+            .chain(
+                self.methods()
+                    .map(|method| (method.name.to_string(), Entity::Method(method))),
+            );
+
+        did_you_mean(name, candidates, limit)
+    }
+
+    /// Changes whenever a page is inserted or removed, to tell whether anything computed from the
+    /// manual is outdated
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn key(name: &str) -> String {
+        FullyQualifiedName::from(name).to_string().to_lowercase()
+    }
+
+    /// Functions, sorted by name
+    pub fn functions(&self) -> impl Iterator<Item = &FunctionDefinition> {
+        self.functions.values()
+    }
+
+    /// Classes, interfaces and traits, sorted by name
+    pub fn classes(&self) -> impl Iterator<Item = &Class> {
+        self.classes.values()
+    }
+
+    /// Top-level sections of the language reference, in the order of their source files
+    pub fn chapters(&self) -> impl Iterator<Item = &Section> {
+        self.chapters.values()
+    }
+
+    /// Looks a section of the language reference up by its [key](Section::key), at any depth
+    ///
+    /// Falls back to the first section with this title, for pages saved by title before
+    pub fn section(&self, key: &str) -> Option<&Section> {
+        let sections = || self.chapters().flat_map(Section::table_of_contents);
+
+        sections()
+            .find(|(_, section)| section.key() == key)
+            .or_else(|| sections().find(|(_, section)| section.title == key))
+            .map(|(_, section)| section)
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = &PredefinedVariable> {
+        self.variables.iter()
+    }
+
+    /// Methods of every class, sorted by class then name
+    pub fn methods(&self) -> impl Iterator<Item = &FunctionDefinition> {
+        self.methods.values()
+    }
+
+    /// Predefined constants, sorted by name
+    pub fn constants(&self) -> impl Iterator<Item = &Constant> {
+        self.constants.values()
+    }
+
+    /// `php.ini` directives, sorted by name
+    pub fn ini_directives(&self) -> impl Iterator<Item = &IniDirective> {
+        self.ini_directives.values()
+    }
+
+    /// Aliases along with the function they are another name of, sorted by alias
+    pub fn aliases(&self) -> impl Iterator<Item = (&FullyQualifiedName, &FullyQualifiedName)> {
+        self.aliases.iter()
+    }
+
+    /// Every entity of the manual: functions, classes, chapters, variables, methods, constants
+    /// then ini directives
+    pub fn iter(&self) -> impl Iterator<Item = Entity<'_>> {
+        self.functions()
+            .map(Entity::Function)
+            .chain(self.classes().map(Entity::Class))
+            .chain(self.chapters().map(Entity::Chapter))
+            .chain(self.variables().map(Entity::Variable))
+            .chain(self.methods().map(Entity::Method))
+            .chain(self.constants().map(Entity::Constant))
+            .chain(self.ini_directives().map(Entity::IniDirective))
+    }
+
+    /// The file an entity was parsed from, only known for the entities of extensions
+    pub fn source(&self, entity: Entity) -> Option<&Path> {
+        match entity {
+            Entity::Function(function) => self.function_sources.get(&function.name),
+            Entity::Class(class) => self.class_sources.get(&class.name),
+            Entity::Method(method) => self.method_sources.get(&method.name),
+            Entity::Constant(constant) => self.constant_sources.get(&constant.name),
+            Entity::IniDirective(directive) => self.ini_directive_sources.get(&directive.name),
+            Entity::Chapter(_) | Entity::Variable(_) => None,
+        }
+        .map(PathBuf::as_path)
+    }
+
+    /// The first version of PHP a function, class or method is available in, if its extension
+    /// documents it
+    pub fn since(&self, entity: Entity) -> Option<PhpVersion> {
+        let name = match entity {
+            Entity::Function(function) => &function.name,
+            Entity::Class(class) => &class.name,
+            Entity::Method(method) => &method.name,
+            Entity::Chapter(_)
+            | Entity::Variable(_)
+            | Entity::Constant(_)
+            | Entity::IniDirective(_) => return None,
+        };
+
+        self.versions.get(&Self::key(&name.to_string())).copied()
+    }
+
+    /// The extension documenting an entity, e.g. `strings` for `strlen`
+    pub fn extension(&self, entity: Entity) -> Option<&str> {
+        self.source(entity).and_then(extension_of)
+    }
+
+    /// Entities of an extension, e.g. `strings` or `random`
+    pub fn by_extension<'a>(&'a self, extension: &'a str) -> impl Iterator<Item = Entity<'a>> {
+        self.iter().filter(move |entity| {
+            self.extension(*entity)
+                .is_some_and(|entity_extension| entity_extension.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    /// Every extension documenting at least one entity
+    pub fn extensions(&self) -> BTreeSet<&str> {
+        self.iter()
+            .filter_map(|entity| self.extension(entity))
+            .collect()
+    }
+
+    /// The entity parsed from the file at `path`, if it was not replaced by another file since
+    fn entity_at(&self, path: &Path) -> Option<Entity<'_>> {
+        let is_source = |source: Option<&PathBuf>| source.is_some_and(|source| source == path);
+
+        match self.pages.get(path)? {
+            PageEntry::Function(name) => is_source(self.function_sources.get(name))
+                .then(|| self.functions.get(name).map(Entity::Function))?,
+            PageEntry::Class(name) => is_source(self.class_sources.get(name))
+                .then(|| self.classes.get(name).map(Entity::Class))?,
+            PageEntry::Chapter => self.chapters.get(path).map(Entity::Chapter),
+            PageEntry::Variable(name) => self
+                .variables()
+                .find(|variable| variable.name == *name)
+                .map(Entity::Variable),
+            PageEntry::Method(name) => is_source(self.method_sources.get(name))
+                .then(|| self.methods.get(name).map(Entity::Method))?,
+            PageEntry::Versions(_)
+            | PageEntry::Alias(_)
+            | PageEntry::Constants(_)
+            | PageEntry::IniDirectives(_) => None,
+        }
+    }
+
+    /// Entities whose text matches the query, the most relevant first
+    pub fn search_text<'a>(&'a self, query: &Query) -> impl Iterator<Item = TextHit<'a>> {
+        self.text_index
+            .search(query)
+            .into_iter()
+            .filter_map(move |(path, score)| {
+                let entity = self.entity_at(path)?;
+
+                Some(TextHit {
+                    entity,
+                    score,
+                    path,
+                })
+            })
+    }
+
+    /// Files which could not be parsed by [`Manual::load`]
+    pub fn failures(&self) -> &[(PathBuf, PageError)] {
+        &self.failures
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::{
+        Entity, Manual, ManualOptions, Page, PageKind, TextHit, extension_of, parse_page_text,
+    };
+    use crate::{
+        XmlParser,
+        chapter::Section,
+        class::Class,
+        function::FunctionDefinition,
+        name::FullyQualifiedName,
+        search::{Query, Snippet},
+    };
+
+    #[rstest::rstest]
+    #[case(".data/reference/strings/functions/strlen.xml", Some("strings"))]
+    #[case(".data/reference/random/random.randomizer.xml", Some("random"))]
+    #[case(".data/language/oop5/basic.xml", None)]
+    pub fn test_extension_of(#[case] path: &str, #[case] extension: Option<&str>) {
+        assert_eq!(extension_of(Path::new(path)), extension);
+    }
+
+    #[test]
+    pub fn test_lookups() {
+        let mut manual = Manual::default();
+        manual.insert(
+            PathBuf::from(".data/reference/strings/functions/strlen.xml"),
+            FunctionDefinition::stub("strlen")
+                .described("Get string length")
+                .page(),
+        );
+        manual.insert(
+            PathBuf::from(".data/reference/random/random.randomizer.xml"),
+            Page::Class(Some(Class {
+                name: FullyQualifiedName::from("Random\\Randomizer"),
+                kind: Default::default(),
+                modifiers: Vec::new(),
+                attributes: Vec::new(),
+                extends: None,
+                implements: Vec::new(),
+                description: Vec::new(),
+            })),
+        );
+
+        assert!(manual.function("STRLEN").is_some());
+        assert!(manual.function("\\strlen").is_some());
+        assert!(manual.function("mb_strlen").is_none());
+        assert!(manual.class("\\random\\randomizer").is_some());
+        assert!(manual.class("Randomizer").is_none());
+        assert!(matches!(
+            manual.did_you_mean("strlne", 5)[..],
+            [Entity::Function(function)] if function.name.name == "strlen"
+        ));
+        assert!(manual.did_you_mean("random\\randomiser", 5).len() == 1);
+
+        let strings = manual.by_extension("strings").collect::<Vec<_>>();
+        assert!(
+            matches!(strings[..], [Entity::Function(function)] if function.name.name == "strlen")
+        );
+        assert_eq!(
+            manual.extensions().into_iter().collect::<Vec<_>>(),
+            ["random", "strings"]
+        );
+        assert_eq!(manual.iter().count(), 2);
+    }
+
+    #[rstest::rstest]
+    #[case("reference/strings/functions/strlen.xml", Some(PageKind::Function))]
+    #[case("language/predefined/variables/server.xml", Some(PageKind::Variable))]
+    #[case("language/oop5/basic.xml", Some(PageKind::Chapter))]
+    #[case("reference/random/random.randomizer.xml", Some(PageKind::Class))]
+    #[case(
+        "reference/random/random/randomizer/getint.xml",
+        Some(PageKind::Method)
+    )]
+    #[case("reference/math/constants.xml", Some(PageKind::Constants))]
+    #[case("reference/info/ini.xml", Some(PageKind::IniDirectives))]
+    #[case("reference/strings/functions/strlen.txt", None)]
+    pub fn test_page_kind_of(#[case] path: &str, #[case] kind: Option<PageKind>) {
+        let root = Path::new("/docs/en");
+
+        assert_eq!(PageKind::of(root, &root.join(path)), kind);
+        assert_eq!(PageKind::of(root, Path::new(path)), None);
+    }
+
+    #[test]
+    pub fn test_chapter_order() {
+        let section = |title: &str| {
+            Page::Chapter(Some(Section {
+                id: None,
+                title: title.to_string(),
+                paragraphs: Vec::new(),
+                sections: Vec::new(),
+            }))
+        };
+
+        let mut manual = Manual::default();
+        manual.insert(PathBuf::from("language/types.xml"), section("Types"));
+        manual.insert(
+            PathBuf::from("language/basic-syntax.xml"),
+            section("Basic syntax"),
+        );
+        manual.insert(
+            PathBuf::from("language/variables.xml"),
+            section("Variables"),
+        );
+        manual.insert(
+            PathBuf::from("language/basic-syntax.xml"),
+            section("PHP tags"),
+        );
+
+        assert_eq!(
+            manual
+                .chapters()
+                .map(|section| section.title.as_str())
+                .collect::<Vec<_>>(),
+            ["PHP tags", "Types", "Variables"]
+        );
+    }
+
+    #[test]
+    pub fn test_section_lookup() {
+        let section = |id: Option<&str>, title: &str, sections: Vec<Section>| Section {
+            id: id.map(str::to_string),
+            title: title.to_string(),
+            paragraphs: Vec::new(),
+            sections,
+        };
+
+        let mut manual = Manual::default();
+        manual.insert(
+            PathBuf::from("language/oop5.xml"),
+            Page::Chapter(Some(section(
+                Some("language.oop5"),
+                "Classes and Objects",
+                vec![
+                    section(Some("oop5.intro"), "Introduction", Vec::new()),
+                    section(None, "Untitled notes", Vec::new()),
+                ],
+            ))),
+        );
+        manual.insert(
+            PathBuf::from("language/types.xml"),
+            Page::Chapter(Some(section(
+                Some("language.types"),
+                "Types",
+                vec![section(
+                    Some("language.types.intro"),
+                    "Introduction",
+                    Vec::new(),
+                )],
+            ))),
+        );
+
+        let id = |key: &str| {
+            manual
+                .section(key)
+                .and_then(|section| section.id.as_deref())
+        };
+        assert_eq!(id("language.types.intro"), Some("language.types.intro"));
+        assert_eq!(id("oop5.intro"), Some("oop5.intro"));
+        // Saved by title
+        assert_eq!(id("Introduction"), Some("oop5.intro"));
+        assert_eq!(
+            manual.section("Untitled notes").map(Section::key),
+            Some("Untitled notes")
+        );
+        assert!(manual.section("language.oop5.intro").is_none());
+    }
+
+    #[test]
+    pub fn test_reloading() {
+        let strlen = |name: &str| FunctionDefinition::stub(name).page();
+        let path = PathBuf::from(".data/reference/strings/functions/strlen.xml");
+
+        let mut manual = Manual::default();
+        manual.insert(path.clone(), strlen("strlen"));
+        // The page was renamed while editing it
+        manual.insert(path.clone(), strlen("str_len"));
+
+        assert!(manual.function("strlen").is_none());
+        assert!(manual.function("str_len").is_some());
+
+        let revision = manual.revision();
+        manual.remove(&path);
+        assert_eq!(manual.iter().count(), 0);
+        assert!(manual.revision() > revision);
+    }
+
+    #[test]
+    pub fn test_loading_fixtures() -> Result<(), Box<dyn std::error::Error>> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        let manual = Manual::load(&root, ManualOptions::default())?;
+
+        assert!(manual.failures().is_empty(), "{:?}", manual.failures());
+        for function in manual.functions() {
+            assert_eq!(manual.function(&function.name.to_string()), Some(function));
+        }
+        for class in manual.classes() {
+            assert_eq!(manual.class(&class.name.to_string()), Some(class));
+        }
+
+        let implode = manual.function("implode");
+        assert!(implode.is_some());
+        assert_eq!(manual.function("join"), implode);
+        assert_eq!(manual.function("JOIN"), implode);
+        assert!(manual.class("Random\\Randomizer").is_some());
+        assert_eq!(
+            manual
+                .chapters()
+                .map(|chapter| chapter.title.as_str())
+                .collect::<Vec<_>>(),
+            ["Basic syntax"]
+        );
+        assert_eq!(
+            manual
+                .variables()
+                .map(|variable| variable.name.as_str())
+                .collect::<Vec<_>>(),
+            ["$_SERVER"]
+        );
+        assert_eq!(
+            manual
+                .methods()
+                .map(|method| method.name.to_string())
+                .collect::<Vec<_>>(),
+            ["Random\\Randomizer::getInt"]
+        );
+        assert!(manual.method("\\random\\randomizer::GETINT").is_some());
+        assert!(manual.constant("LC_CTYPE").is_some());
+        assert!(manual.constant("lc_ctype").is_none());
+        assert!(
+            manual
+                .ini_directive("memory_limit")
+                .is_some_and(
+                    |directive| manual.extension(Entity::IniDirective(directive)) == Some("info")
+                )
+        );
+        assert_eq!(
+            manual.extensions().into_iter().collect::<Vec<_>>(),
+            ["info", "random", "strings"]
+        );
+        assert!(
+            manual
+                .function("str_contains")
+                .is_some_and(|function| manual.since(Entity::Function(function)).is_some())
+        );
+
+        let query = Query::new("substring");
+        let hits = manual.search_text(&query).collect::<Vec<_>>();
+        assert!(matches!(
+            hits.first(),
+            Some(TextHit { entity: Entity::Function(function), .. })
+                if function.name.to_string() == "str_contains"
+        ));
+        // Only the terms are kept, the text is parsed again for the snippet
+        let text = parse_page_text(&XmlParser::default(), hits[0].path)?;
+        assert!(Snippet::new(&text, &query).is_some());
+        assert!(
+            manual
+                .search_text(&Query::new("uniformly selected"))
+                .any(|hit| matches!(hit.entity, Entity::Method(_)))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A possibly namespaced name, e.g. `Random\Randomizer` or `strlen`
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct FullyQualifiedName {
+    /// Namespace segments, empty for the global namespace
+    pub namespace: Vec<String>,
+    pub name: String,
+}
+
+impl FullyQualifiedName {
+    pub fn is_namespaced(&self) -> bool {
+        !self.namespace.is_empty()
+    }
+
+    /// The namespace, joined with `\`, e.g. `Random\Engine`
+    pub fn namespace(&self) -> Option<String> {
+        self.is_namespaced().then(|| self.namespace.join("\\"))
+    }
+
+    /// Whether `name` designates this entity, either by its short or fully qualified name
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.trim().trim_start_matches('\\');
+
+        self.name.eq_ignore_ascii_case(name) || self.to_string().eq_ignore_ascii_case(name)
+    }
+}
+
+impl fmt::Display for FullyQualifiedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for segment in &self.namespace {
+            write!(f, "{segment}\\")?;
+        }
+
+        write!(f, "{}", self.name)
+    }
+}
+
+impl From<&str> for FullyQualifiedName {
+    fn from(value: &str) -> Self {
+        let value = value.trim().trim_start_matches('\\');
+
+        // Methods are documented as `Random\Randomizer::getInt`, the `::` part is not namespaced
+        let (path, member) = match value.split_once("::") {
+            Some((path, member)) => (path, Some(member)),
+            None => (value, None),
+        };
+
+        let mut namespace = path.split('\\').map(str::to_string).collect::<Vec<_>>();
+        let mut name = namespace.pop().unwrap_or_default();
+
+        if let Some(member) = member {
+            name = format!("{name}::{member}");
+        }
+
+        Self { namespace, name }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FullyQualifiedName;
+
+    #[rstest::rstest]
+    #[case("strlen", &[], "strlen")]
+    #[case("\\Random\\Randomizer", &["Random"], "Randomizer")]
+    #[case("Random\\Randomizer::getInt", &["Random"], "Randomizer::getInt")]
+    #[case("Dom\\HTMLDocument", &["Dom"], "HTMLDocument")]
+    pub fn test_name_parsing(#[case] source: &str, #[case] namespace: &[&str], #[case] name: &str) {
+        let fqn = FullyQualifiedName::from(source);
+
+        assert_eq!(fqn.namespace, namespace);
+        assert_eq!(fqn.name, name);
+        assert_eq!(fqn.to_string(), source.trim_start_matches('\\'));
+    }
+
+    #[test]
+    pub fn test_name_matching() {
+        let fqn = FullyQualifiedName::from("FFI\\CData");
+
+        assert!(fqn.matches("CData"));
+        assert!(fqn.matches("\\ffi\\cdata"));
+        assert!(!fqn.matches("FFI"));
+    }
+}
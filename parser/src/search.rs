@@ -0,0 +1,539 @@
+use std::{
+    borrow::Borrow,
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::Hash,
+    ops::{Bound, Range},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A part of a page, matches in the name count more than in the examples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Field {
+    Name,
+    /// The one line purpose of the page, e.g. `Get string length`
+    Summary,
+    Description,
+    Parameters,
+    Examples,
+}
+
+impl Field {
+    fn weight(self) -> f32 {
+        match self {
+            Field::Name => 3.0,
+            Field::Summary => 2.5,
+            Field::Description => 1.0,
+            Field::Parameters => 0.7,
+            Field::Examples => 0.5,
+        }
+    }
+}
+
+/// Plain text of a page, as indexed for full-text search
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Document {
+    pub fields: Vec<(Field, String)>,
+}
+
+impl Document {
+    pub fn push(&mut self, field: Field, text: &str) {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            self.fields.push((field, text));
+        }
+    }
+}
+
+/// Term statistics of a [`Document`], all a [`TextIndex`] keeps of it as its text is only needed
+/// for [`Snippet`]s
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Terms {
+    /// Occurrences of each distinct term, weighted by the field they are in
+    frequencies: Vec<(String, f32)>,
+    /// Number of terms of the document, repeated ones included
+    length: usize,
+}
+
+impl From<&Document> for Terms {
+    fn from(document: &Document) -> Self {
+        let mut frequencies = HashMap::<String, f32>::new();
+        let mut length = 0;
+        for (field, text) in &document.fields {
+            for (_, term) in tokenize(text) {
+                *frequencies.entry(term).or_default() += field.weight();
+                length += 1;
+            }
+        }
+
+        Self {
+            frequencies: frequencies.into_iter().collect(),
+            length,
+        }
+    }
+}
+
+/// Words too common to tell pages apart
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "does", "for", "from", "how", "i",
+    "if", "in", "into", "is", "it", "its", "of", "on", "or", "that", "the", "this", "to", "what",
+    "when", "which", "with",
+];
+
+/// Splits text into lowercased and stemmed terms, along with their byte range in the text
+///
+/// Words are split on anything which is not alphanumeric, so that `preg_split` gives `preg` and
+/// `split`
+pub fn tokenize(text: &str) -> impl Iterator<Item = (Range<usize>, String)> + '_ {
+    text.char_indices()
+        .filter(|(i, char)| {
+            char.is_alphanumeric()
+                && text[..*i]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|previous| !previous.is_alphanumeric())
+        })
+        .map(|(start, _)| {
+            let end = text[start..]
+                .find(|char: char| !char.is_alphanumeric())
+                .map_or(text.len(), |length| start + length);
+
+            (start..end, text[start..end].to_lowercase())
+        })
+        .filter(|(_, word)| !STOP_WORDS.contains(&word.as_str()))
+        .map(|(range, word)| (range, stem(&word)))
+}
+
+/// Strips the plural and third person suffixes, enough for `splits` to match `split`
+fn stem(word: &str) -> String {
+    if word.chars().count() <= 3 || word.ends_with("ss") {
+        return word.to_string();
+    }
+
+    if let Some(stem) = word.strip_suffix("ies") {
+        return format!("{stem}y");
+    }
+
+    if let Some(stem) = word.strip_suffix("es") {
+        if ["sh", "ch", "x", "ss"]
+            .iter()
+            .any(|suffix| stem.ends_with(suffix))
+        {
+            return stem.to_string();
+        }
+    }
+
+    word.strip_suffix('s').unwrap_or(word).to_string()
+}
+
+/// Terms of a query, the last one also matches the terms it is a prefix of while it is being typed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    terms: Vec<String>,
+    prefix: Option<String>,
+}
+
+impl Query {
+    /// Prefixes shorter than this are not expanded, as they match too many terms
+    const MIN_PREFIX_LENGTH: usize = 3;
+
+    pub fn new(query: &str) -> Self {
+        let mut terms = tokenize(query).map(|(_, term)| term).collect::<Vec<_>>();
+        // The raw word, as stemming would turn `process` into `proces` while typing `processes`
+        let prefix = query
+            .rsplit(|char: char| !char.is_alphanumeric())
+            .next()
+            .filter(|word| word.chars().count() >= Self::MIN_PREFIX_LENGTH)
+            .map(str::to_lowercase);
+        terms.sort_unstable();
+        terms.dedup();
+
+        Self { terms, prefix }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.prefix.is_none()
+    }
+
+    fn matches(&self, term: &str) -> bool {
+        self.terms.iter().any(|query_term| query_term == term)
+            || self
+                .prefix
+                .as_ref()
+                .is_some_and(|prefix| term.starts_with(prefix.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Posting {
+    document: usize,
+    /// Occurrences of the term in the document, weighted by the field they are in
+    frequency: f32,
+}
+
+#[derive(Debug)]
+struct IndexedDocument<K> {
+    key: K,
+    length: usize,
+    /// Distinct terms of the document, to take it out of their postings once removed
+    terms: Vec<String>,
+}
+
+/// An inverted index of the [`Terms`] of documents, ranking them against queries with BM25
+#[derive(Debug)]
+pub struct TextIndex<K> {
+    /// Indexed documents by id, `None` once removed until the id is reused
+    documents: Vec<Option<IndexedDocument<K>>>,
+    /// Ids of the removed documents, for the next insertions
+    free_ids: Vec<usize>,
+    ids: HashMap<K, usize>,
+    postings: BTreeMap<String, Vec<Posting>>,
+    total_length: usize,
+}
+
+impl<K> Default for TextIndex<K> {
+    fn default() -> Self {
+        Self {
+            documents: Vec::new(),
+            free_ids: Vec::new(),
+            ids: HashMap::new(),
+            postings: BTreeMap::new(),
+            total_length: 0,
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> TextIndex<K> {
+    // Usual BM25 parameters, for term frequency saturation and document length normalization
+    const K1: f32 = 1.2;
+    const B: f32 = 0.75;
+
+    /// Indexes the terms of a document, replacing any document previously indexed with the same
+    /// key
+    pub fn insert(&mut self, key: K, terms: Terms) {
+        self.remove(&key);
+
+        let id = self.free_ids.pop().unwrap_or_else(|| {
+            self.documents.push(None);
+            self.documents.len() - 1
+        });
+        for (term, frequency) in &terms.frequencies {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .push(Posting {
+                    document: id,
+                    frequency: *frequency,
+                });
+        }
+
+        self.documents[id] = Some(IndexedDocument {
+            key: key.clone(),
+            length: terms.length,
+            terms: terms
+                .frequencies
+                .into_iter()
+                .map(|(term, _)| term)
+                .collect(),
+        });
+        self.ids.insert(key, id);
+        self.total_length += terms.length;
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let Some(id) = self.ids.remove(key) else {
+            return;
+        };
+        let Some(document) = self.documents[id].take() else {
+            return;
+        };
+        self.free_ids.push(id);
+
+        for term in &document.terms {
+            if let Some(postings) = self.postings.get_mut(term) {
+                postings.retain(|posting| posting.document != id);
+                if postings.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        self.total_length -= document.length;
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Documents matching at least one term of the query, the most relevant first
+    pub fn search(&self, query: &Query) -> Vec<(&K, f32)> {
+        let document_count = self.len() as f32;
+        let average_length = self.total_length as f32 / document_count.max(1.0);
+
+        let mut matched_terms = query
+            .terms
+            .iter()
+            .filter_map(|term| self.postings.get_key_value(term))
+            .collect::<Vec<_>>();
+        if let Some(prefix) = &query.prefix {
+            matched_terms.extend(
+                self.postings
+                    .range::<str, _>((Bound::Included(prefix.as_str()), Bound::Unbounded))
+                    .take_while(|(term, _)| term.starts_with(prefix.as_str())),
+            );
+        }
+        matched_terms.sort_by_key(|(term, _)| *term);
+        matched_terms.dedup_by_key(|(term, _)| *term);
+
+        let mut scores = HashMap::<usize, f32>::new();
+        for (_, postings) in matched_terms {
+            let frequency = postings.len() as f32;
+            let idf = (1.0 + (document_count - frequency + 0.5) / (frequency + 0.5)).ln();
+
+            for posting in postings {
+                let Some(document) = &self.documents[posting.document] else {
+                    continue;
+                };
+
+                let normalization =
+                    Self::K1 * (1.0 - Self::B + Self::B * document.length as f32 / average_length);
+                *scores.entry(posting.document).or_default() +=
+                    idf * posting.frequency * (Self::K1 + 1.0)
+                        / (posting.frequency + normalization);
+            }
+        }
+
+        let mut results = scores
+            .into_iter()
+            .filter_map(|(id, score)| Some((&self.documents[id].as_ref()?.key, score)))
+            .collect::<Vec<_>>();
+        results.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        results
+    }
+}
+
+/// An excerpt of a document around the terms matching a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub field: Field,
+    pub text: String,
+    /// Byte ranges of the matching terms in the text
+    pub highlights: Vec<Range<usize>>,
+}
+
+impl Snippet {
+    /// Characters kept before the first match
+    const LEADING_CONTEXT: usize = 24;
+    const LENGTH: usize = 120;
+
+    /// The excerpt of the field with the most matches, the name excluded as it is already shown
+    pub fn new(document: &Document, query: &Query) -> Option<Self> {
+        let (field, text, matches) = document
+            .fields
+            .iter()
+            .filter(|(field, _)| *field != Field::Name)
+            .map(|(field, text)| {
+                let matches = tokenize(text)
+                    .filter(|(_, term)| query.matches(term))
+                    .collect::<Vec<_>>();
+
+                (*field, text, matches)
+            })
+            // The field matching the most distinct terms, the first one on ties
+            .max_by_key(|(field, _, matches)| {
+                (
+                    matches
+                        .iter()
+                        .map(|(_, term)| term)
+                        .collect::<HashSet<_>>()
+                        .len(),
+                    Reverse(*field),
+                )
+            })
+            .filter(|(_, _, matches)| !matches.is_empty())?;
+        let first_match = matches[0].0.start;
+
+        let start = text[..first_match]
+            .char_indices()
+            .rev()
+            .nth(Self::LEADING_CONTEXT)
+            // Start on a word boundary
+            .map_or(0, |(i, _)| match text[i..first_match].find(' ') {
+                Some(space) => i + space + 1,
+                None => i,
+            });
+        let end = text[start..]
+            .char_indices()
+            .nth(Self::LENGTH)
+            .map_or(text.len(), |(i, _)| start + i);
+
+        let ellipsis = if start > 0 { "…" } else { "" };
+        let highlights = matches
+            .into_iter()
+            .map(|(range, _)| range)
+            .filter(|range| range.start >= start && range.end <= end)
+            .map(|range| range.start - start + ellipsis.len()..range.end - start + ellipsis.len())
+            .collect();
+
+        Some(Self {
+            field,
+            text: format!(
+                "{ellipsis}{}{}",
+                &text[start..end],
+                if end < text.len() { "…" } else { "" }
+            ),
+            highlights,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Document, Field, Query, Snippet, Terms, TextIndex, tokenize};
+
+    fn document(name: &str, summary: &str, description: &str) -> Document {
+        let mut document = Document::default();
+        document.push(Field::Name, name);
+        document.push(Field::Summary, summary);
+        document.push(Field::Description, description);
+
+        document
+    }
+
+    fn terms(name: &str, summary: &str, description: &str) -> Terms {
+        Terms::from(&document(name, summary, description))
+    }
+
+    fn index() -> TextIndex<&'static str> {
+        let mut index = TextIndex::default();
+        index.insert(
+            "preg_split",
+            terms(
+                "preg_split",
+                "Split string by a regular expression",
+                "Split the given string by a regular expression.",
+            ),
+        );
+        index.insert(
+            "explode",
+            terms(
+                "explode",
+                "Split a string by a string",
+                "Returns an array of strings, each of which is a substring of string formed by splitting it on boundaries formed by the string separator.",
+            ),
+        );
+        index.insert(
+            "strlen",
+            terms(
+                "strlen",
+                "Get string length",
+                "Returns the length of the given string.",
+            ),
+        );
+
+        index
+    }
+
+    #[rstest::rstest]
+    #[case("preg_split", &["preg", "split"])]
+    #[case("Splits the strings", &["split", "string"])]
+    #[case("Matches classes, processes", &["match", "class", "process"])]
+    pub fn test_tokenize(#[case] text: &str, #[case] terms: &[&str]) {
+        assert_eq!(
+            tokenize(text).map(|(_, term)| term).collect::<Vec<_>>(),
+            terms
+        );
+    }
+
+    #[test]
+    pub fn test_search_ranking() {
+        let index = index();
+
+        let keys = |query: &str| {
+            index
+                .search(&Query::new(query))
+                .into_iter()
+                .map(|(key, _)| *key)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            keys("which function splits a string by regular expression")[0],
+            "preg_split"
+        );
+        assert_eq!(keys("length")[..], ["strlen"]);
+        // The last word is matched as a prefix while typing it
+        assert_eq!(keys("leng")[..], ["strlen"]);
+        assert!(keys("the").is_empty());
+    }
+
+    #[test]
+    pub fn test_removal() {
+        let mut index = index();
+        index.remove("preg_split");
+
+        assert_eq!(index.len(), 2);
+        assert!(index.search(&Query::new("regular")).is_empty());
+        assert_eq!(index.search(&Query::new("split")).len(), 1);
+    }
+
+    #[test]
+    pub fn test_reinsertion_reuses_ids() {
+        let mut index = index();
+        for _ in 0..10 {
+            index.insert(
+                "strlen",
+                terms("strlen", "Get string length", "Returns the length."),
+            );
+            index.remove("explode");
+            index.insert(
+                "explode",
+                terms("explode", "Split a string by a string", ""),
+            );
+        }
+
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.documents.len(), 3);
+        assert_eq!(
+            index
+                .search(&Query::new("length"))
+                .into_iter()
+                .map(|(key, _)| *key)
+                .collect::<Vec<_>>(),
+            ["strlen"]
+        );
+    }
+
+    #[test]
+    pub fn test_snippet() {
+        let document = document(
+            "explode",
+            "Split a string by a string",
+            "Returns an array of strings, each of which is a substring of string formed by splitting it on boundaries formed by the string separator.",
+        );
+
+        let snippet = Snippet::new(&document, &Query::new("boundaries")).unwrap();
+        assert_eq!(snippet.field, Field::Description);
+        assert!(snippet.text.starts_with('…'));
+        assert_eq!(
+            snippet
+                .highlights
+                .iter()
+                .map(|range| &snippet.text[range.clone()])
+                .collect::<Vec<_>>(),
+            ["boundaries"]
+        );
+
+        // The name is not worth a snippet, as it is displayed anyway
+        assert!(Snippet::new(&document, &Query::new("explode")).is_none());
+    }
+}
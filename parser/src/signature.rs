@@ -0,0 +1,335 @@
+use std::collections::BTreeSet;
+
+use super::{
+    function::{FunctionDefinition, Parameter},
+    r#type::TypeHint,
+};
+
+/// Lowercased names of the members of a type, e.g. `{array, false}` for `array|false`
+type Types = BTreeSet<String>;
+
+/// Types which are not class names
+const BUILTIN_TYPES: &[&str] = &[
+    "array", "bool", "callable", "false", "float", "int", "iterable", "mixed", "never", "null",
+    "object", "resource", "static", "string", "true", "void",
+];
+
+/// Distance added for every required parameter of a function the signature does not mention
+const MISSING_PARAMETER_DISTANCE: u32 = 2;
+
+fn normalize(name: &str) -> String {
+    let name = name.trim().trim_start_matches('\\').to_lowercase();
+
+    match name.as_str() {
+        "boolean" => "bool".to_string(),
+        "integer" => "int".to_string(),
+        "double" => "float".to_string(),
+        "callback" => "callable".to_string(),
+        _ => name,
+    }
+}
+
+fn parse_types(text: &str) -> Option<Types> {
+    // Parameter names are allowed, e.g. `array $array`
+    let text = text.split_whitespace().next()?;
+    let (nullable, text) = match text.strip_prefix('?') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+
+    let mut types = text
+        .split('|')
+        .map(normalize)
+        .filter(|name| !name.is_empty())
+        .collect::<Types>();
+    if types.is_empty() {
+        return None;
+    }
+    if nullable {
+        types.insert("null".to_string());
+    }
+
+    Some(types)
+}
+
+fn hint_types(type_hint: &TypeHint) -> Types {
+    type_hint
+        .members()
+        .into_iter()
+        .map(|member| normalize(member))
+        .collect()
+}
+
+/// How far `member` has to be widened to be one of the `types`, e.g. `int` to `float`
+fn widening_distance(member: &str, types: &Types) -> Option<u32> {
+    let has = |name: &str| types.contains(name);
+
+    match member {
+        "int" if has("float") => Some(1),
+        "true" | "false" if has("bool") => Some(1),
+        "array" if has("iterable") => Some(1),
+        _ if !BUILTIN_TYPES.contains(&member) && has("object") => Some(2),
+        _ if has("mixed") => Some(3),
+        _ => None,
+    }
+}
+
+/// How far a parameter of the `declared` types is from accepting values of the `queried` types,
+/// `None` if it does not accept them
+fn accepted_distance(queried: &Types, declared: &Types) -> Option<u32> {
+    let mut distance = 0;
+    for member in queried {
+        if !declared.contains(member) {
+            distance += widening_distance(member, declared)?;
+        }
+    }
+
+    // Accepting more than asked for, nullable parameters aside
+    let extra_types = declared
+        .iter()
+        .filter(|member| !queried.contains(*member) && *member != "null" && *member != "mixed")
+        .count() as u32;
+
+    Some(distance + extra_types)
+}
+
+/// How far a function returning the `declared` types is from returning the `expected` ones, `None`
+/// if it returns none of them
+fn returned_distance(expected: &Types, declared: &Types) -> Option<u32> {
+    let mut distance = 0;
+    let mut matches = false;
+    for member in declared {
+        if expected.contains(member) {
+            matches = true;
+            continue;
+        }
+
+        match widening_distance(member, expected) {
+            // Returned on failure, `array|false` does not return a `bool`
+            _ if member == "false" || member == "null" => distance += 1,
+            Some(member_distance) => {
+                matches = true;
+                distance += member_distance;
+            }
+            // `mixed` may be anything expected, only less precisely
+            None if member == "mixed" => {
+                matches = true;
+                distance += 3;
+            }
+            None => distance += 1,
+        }
+    }
+
+    // Returning less than expected, e.g. `int` for `int|string`
+    distance += expected
+        .iter()
+        .filter(|member| !declared.contains(*member))
+        .count() as u32;
+
+    matches.then_some(distance)
+}
+
+/// The shape of a function, to find functions without knowing their name: `array, callable ->
+/// array` matches the functions taking an array and a callable, in any order, and returning an
+/// array
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureQuery {
+    parameters: Vec<Types>,
+    /// `None` to accept any return type, e.g. `array, callable ->`
+    return_type: Option<Types>,
+}
+
+impl SignatureQuery {
+    pub const ARROW: &str = "->";
+
+    /// Parses `type, type -> type`, `None` unless the query contains the arrow. Types can be
+    /// unions (`int|string`) and nullable (`?callable`)
+    pub fn parse(query: &str) -> Option<Self> {
+        let (parameters, return_type) = query.split_once(Self::ARROW)?;
+
+        let parameters = parameters
+            .split(',')
+            .filter(|parameter| !parameter.trim().is_empty())
+            .map(parse_types)
+            .collect::<Option<Vec<_>>>()?;
+        let return_type = match return_type.trim() {
+            "" => None,
+            return_type => Some(parse_types(return_type)?),
+        };
+
+        Some(Self {
+            parameters,
+            return_type,
+        })
+    }
+
+    /// How far the function is from the signature, `0` being an exact match and `None` no match
+    ///
+    /// Functions accepting or returning wider types than asked for (e.g. `array|false` for
+    /// `array`), taking their parameters in another order or requiring more of them are further
+    pub fn distance(&self, function: &FunctionDefinition) -> Option<u32> {
+        let return_distance = match &self.return_type {
+            Some(expected) => returned_distance(expected, &hint_types(&function.return_type))?,
+            None => 0,
+        };
+
+        let parameters = function
+            .arguments
+            .iter()
+            .map(|parameter| (hint_types(&parameter.r#type), parameter))
+            .collect::<Vec<_>>();
+        let mut best = Option::<u32>::None;
+        assign(
+            &self.parameters,
+            &parameters,
+            &mut vec![false; parameters.len()],
+            None,
+            0,
+            &mut best,
+        );
+
+        Some(return_distance + best?)
+    }
+}
+
+/// Assigns each queried type to a distinct parameter accepting it, keeping the distance of the
+/// closest assignment in `best`
+fn assign(
+    queried: &[Types],
+    parameters: &[(Types, &Parameter)],
+    assigned: &mut [bool],
+    previous: Option<usize>,
+    distance: u32,
+    best: &mut Option<u32>,
+) {
+    if best.is_some_and(|best| distance >= best) {
+        return;
+    }
+
+    let Some((types, queried)) = queried.split_first() else {
+        let missing_parameters = parameters
+            .iter()
+            .zip(assigned.iter())
+            .filter(|((_, parameter), assigned)| {
+                !**assigned && parameter.default_value.is_none() && !parameter.repeat
+            })
+            .count() as u32;
+        let distance = distance + missing_parameters * MISSING_PARAMETER_DISTANCE;
+        *best = Some(best.map_or(distance, |best| best.min(distance)));
+
+        return;
+    };
+
+    for (i, (parameter_types, parameter)) in parameters.iter().enumerate() {
+        if assigned[i] {
+            continue;
+        }
+        let Some(type_distance) = accepted_distance(types, parameter_types) else {
+            continue;
+        };
+        // Arguments are usually asked for in the order of the parameters
+        let order_distance = u32::from(previous.is_some_and(|previous| i < previous));
+
+        // Variadic parameters take any number of arguments
+        assigned[i] = !parameter.repeat;
+        assign(
+            queried,
+            parameters,
+            assigned,
+            Some(i),
+            distance + type_distance + order_distance,
+            best,
+        );
+        assigned[i] = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SignatureQuery;
+    use crate::function::FunctionDefinition;
+
+    /// A function with the given synopsis, e.g. `array_filter`, `array`,
+    /// `["array $array", "?callable $callback = null"]`
+    fn function(name: &str, return_type: &str, parameters: &[&str]) -> FunctionDefinition {
+        parameters.iter().fold(
+            FunctionDefinition::stub(name).returning(return_type),
+            |function, parameter| function.parameter(parameter),
+        )
+    }
+
+    fn functions() -> Vec<FunctionDefinition> {
+        vec![
+            function(
+                "array_filter",
+                "array",
+                &[
+                    "array $array",
+                    "?callable $callback = null",
+                    "int $mode = 0",
+                ],
+            ),
+            function(
+                "array_map",
+                "array",
+                &["?callable $callback", "array $array", "array ...$arrays"],
+            ),
+            function("usort", "true", &["array $array", "callable $callback"]),
+            function(
+                "preg_split",
+                "array|false",
+                &[
+                    "string $pattern",
+                    "string $subject",
+                    "int $limit = -1",
+                    "int $flags = 0",
+                ],
+            ),
+            function(
+                "explode",
+                "array",
+                &[
+                    "string $separator",
+                    "string $string",
+                    "int $limit = PHP_INT_MAX",
+                ],
+            ),
+            function("strlen", "int", &["string $string"]),
+            function("round", "float", &["int|float $num", "int $precision = 0"]),
+        ]
+    }
+
+    /// Names of the matching functions, the closest first
+    fn search(query: &str) -> Vec<String> {
+        let query = SignatureQuery::parse(query).unwrap();
+        let mut matches = functions()
+            .into_iter()
+            .filter_map(|function| Some((query.distance(&function)?, function.name.to_string())))
+            .collect::<Vec<_>>();
+        matches.sort();
+
+        matches.into_iter().map(|(_, name)| name).collect()
+    }
+
+    #[rstest::rstest]
+    #[case("array, callable -> array", &["array_filter", "array_map"])]
+    #[case("callable, array -> array", &["array_map", "array_filter"])]
+    #[case("string, string -> array", &["explode", "preg_split"])]
+    #[case("string -> int", &["strlen"])]
+    #[case("int -> float", &["round"])]
+    #[case("-> bool", &["usort"])]
+    #[case("array, callable ->", &["array_filter", "usort", "array_map"])]
+    pub fn test_signature_search(#[case] query: &str, #[case] expected: &[&str]) {
+        assert_eq!(search(query), expected);
+    }
+
+    #[rstest::rstest]
+    #[case("array_map")]
+    #[case("array, callable")]
+    #[case("array, -> array")]
+    pub fn test_query_parsing(#[case] query: &str) {
+        let expected = query.contains(SignatureQuery::ARROW);
+
+        assert_eq!(SignatureQuery::parse(query).is_some(), expected);
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind},
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, ready},
+    vec,
+};
+
+use futures_core::Stream;
+use tokio::sync::mpsc;
+
+use super::{
+    XmlParser,
+    manual::{ManualOptions, Page, PageError, PageKind, parse_page},
+};
+
+/// How many files of a [`ParseStream`] were parsed so far
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub parsed: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn remaining(&self) -> usize {
+        self.total
+            .saturating_sub(self.parsed)
+            .saturating_sub(self.failed)
+    }
+}
+
+/// A file parsed by a [`ParseStream`]
+#[derive(Debug)]
+pub struct ParsedFile {
+    pub path: PathBuf,
+    pub result: Result<Page, PageError>,
+    /// Progress of the stream, this file included
+    pub progress: Progress,
+}
+
+/// Files left to parse, shared by the workers
+pub(crate) type Queue = Mutex<vec::IntoIter<(PageKind, PathBuf)>>;
+
+/// Parses files from the queue until it is empty, or until `emit` returns `false`
+///
+/// A panic while parsing a file is reported as its [`PageError::Panicked`] rather than taking
+/// down the worker along with the files it parsed
+pub(crate) fn parse_queue(
+    queue: &Queue,
+    mut emit: impl FnMut(PathBuf, Result<Page, PageError>) -> bool,
+) {
+    let xml_parser = XmlParser::default();
+
+    while let Some((kind, path)) = queue.lock().ok().and_then(|mut queue| queue.next()) {
+        let result = catch_unwind(AssertUnwindSafe(|| parse_page(&xml_parser, kind, &path)))
+            .unwrap_or_else(|panic| Err(PageError::Panicked(panic_message(panic.as_ref()))));
+        if !emit(path, result) {
+            break;
+        }
+    }
+}
+
+/// The message `panic!` was given, for the two payloads it produces
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Parses files on a pool of background threads, yielding them in the order they finish
+///
+/// Dropping the stream cancels the parsing, workers stop once done with their current file.
+pub struct ParseStream {
+    receiver: mpsc::UnboundedReceiver<(PathBuf, Result<Page, PageError>)>,
+    progress: Progress,
+}
+
+impl ParseStream {
+    pub fn new(files: Vec<(PageKind, PathBuf)>, options: ManualOptions) -> Self {
+        let progress = Progress {
+            total: files.len(),
+            ..Default::default()
+        };
+        let queue = Arc::new(Queue::new(files.into_iter()));
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        for _ in 0..options.jobs.get().min(progress.total) {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+
+            std::thread::spawn(move || {
+                // Sending fails once the stream is dropped
+                parse_queue(&queue, |path, result| sender.send((path, result)).is_ok());
+            });
+        }
+
+        Self { receiver, progress }
+    }
+
+    pub fn progress(&self) -> Progress {
+        self.progress
+    }
+}
+
+impl Stream for ParseStream {
+    type Item = ParsedFile;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some((path, result)) = ready!(self.receiver.poll_recv(cx)) else {
+            return Poll::Ready(None);
+        };
+
+        match result {
+            Ok(_) => self.progress.parsed += 1,
+            Err(_) => self.progress.failed += 1,
+        }
+
+        Poll::Ready(Some(ParsedFile {
+            path,
+            result,
+            progress: self.progress,
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.progress.remaining();
+
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{num::NonZeroUsize, path::PathBuf, pin::Pin};
+
+    use futures_core::Stream;
+
+    use super::{ParseStream, Progress, panic_message};
+    use crate::manual::{ManualOptions, PageError, PageKind};
+
+    #[rstest::rstest]
+    #[case(Progress { parsed: 1, failed: 1, total: 3 }, 1)]
+    #[case(Progress { parsed: 2, failed: 1, total: 3 }, 0)]
+    // The fields are public, so nothing keeps the counts consistent
+    #[case(Progress { parsed: 3, failed: 1, total: 3 }, 0)]
+    pub fn test_remaining(#[case] progress: Progress, #[case] remaining: usize) {
+        assert_eq!(progress.remaining(), remaining);
+    }
+
+    #[test]
+    pub fn test_panic_message() {
+        let message =
+            |panic: fn()| panic_message(std::panic::catch_unwind(panic).unwrap_err().as_ref());
+
+        assert_eq!(message(|| panic!("static")), "static");
+        assert_eq!(message(|| panic!("formatted {}", 1)), "formatted 1");
+        assert_eq!(message(|| std::panic::panic_any(1)), "unknown panic");
+    }
+
+    #[tokio::test]
+    pub async fn test_stream_progress() {
+        let files = (0..3)
+            .map(|i| {
+                (
+                    PageKind::Function,
+                    PathBuf::from(format!("missing-{i}.xml")),
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut stream = ParseStream::new(
+            files,
+            ManualOptions {
+                jobs: NonZeroUsize::new(2).unwrap(),
+            },
+        );
+
+        let mut events = Vec::new();
+        while let Some(event) = std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await
+        {
+            events.push(event);
+        }
+
+        assert_eq!(events.len(), 3);
+        assert!(
+            events
+                .iter()
+                .all(|event| matches!(event.result, Err(PageError::Xml(_))))
+        );
+        assert_eq!(
+            stream.progress(),
+            Progress {
+                parsed: 0,
+                failed: 3,
+                total: 3
+            }
+        );
+        assert_eq!(events[2].progress.remaining(), 0);
+    }
+}
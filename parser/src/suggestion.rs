@@ -0,0 +1,109 @@
+/// Number of single character insertions, deletions, substitutions and transpositions of adjacent
+/// characters turning `a` into `b`, ignoring case
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.to_lowercase().chars().collect::<Vec<_>>();
+    let b = b.to_lowercase().chars().collect::<Vec<_>>();
+
+    // Distances between the prefixes of `a` and `b`, two rows back to count transpositions
+    let mut before_previous_row = vec![0; b.len() + 1];
+    let mut previous_row = (0..=b.len()).collect::<Vec<_>>();
+    let mut row = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        row[0] = i;
+        for j in 1..=b.len() {
+            let substitution = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (previous_row[j] + 1)
+                .min(row[j - 1] + 1)
+                .min(previous_row[j - 1] + substitution);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before_previous_row[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before_previous_row, &mut previous_row);
+        std::mem::swap(&mut previous_row, &mut row);
+    }
+
+    previous_row[b.len()]
+}
+
+/// How many edits a name can be away from what was typed to still be suggested, a quarter of its
+/// length
+fn max_distance(name: &str) -> usize {
+    name.chars().count().div_ceil(4).clamp(1, 3)
+}
+
+/// The `limit` candidates whose name is closest to `name`, the closest first, leaving out those
+/// too far from it to be what was meant, e.g. `str_contains` for `str_contians`
+///
+/// Candidates as close as each other stay in their order
+pub fn did_you_mean<T>(
+    name: &str,
+    candidates: impl IntoIterator<Item = (String, T)>,
+    limit: usize,
+) -> Vec<T> {
+    let name = name.trim().trim_start_matches('\\');
+    let max_distance = max_distance(name);
+    let length = name.chars().count();
+
+    let mut suggestions = candidates
+        .into_iter()
+        // Cheaper than computing the distance of every candidate
+        .filter(|(candidate, _)| candidate.chars().count().abs_diff(length) <= max_distance)
+        .filter_map(|(candidate, value)| {
+            let distance = edit_distance(name, &candidate);
+
+            (distance <= max_distance).then_some((distance, value))
+        })
+        .collect::<Vec<_>>();
+    suggestions.sort_by_key(|(distance, _)| *distance);
+
+    suggestions
+        .into_iter()
+        .take(limit)
+        .map(|(_, value)| value)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{did_you_mean, edit_distance};
+
+    #[rstest::rstest]
+    #[case("strlen", "strlen", 0)]
+    #[case("STRLEN", "strlen", 0)]
+    #[case("str_contians", "str_contains", 1)]
+    #[case("array_key_exist", "array_key_exists", 1)]
+    #[case("strlen", "mb_strlen", 3)]
+    #[case("", "count", 5)]
+    pub fn test_edit_distance(#[case] a: &str, #[case] b: &str, #[case] distance: usize) {
+        assert_eq!(edit_distance(a, b), distance);
+        assert_eq!(edit_distance(b, a), distance);
+    }
+
+    #[rstest::rstest]
+    #[case("str_contians", &["str_contains"])]
+    #[case("array_key_exist", &["array_key_exists", "array_key_last"])]
+    #[case("strln", &["strlen"])]
+    #[case("\\Strlen", &["strlen"])]
+    #[case("explode", &[])]
+    pub fn test_did_you_mean(#[case] name: &str, #[case] expected: &[&str]) {
+        let names = [
+            "array_key_exists",
+            "array_key_last",
+            "mb_strlen",
+            "str_contains",
+            "strlen",
+            "strpos",
+        ];
+
+        let suggestions = did_you_mean(
+            name,
+            names.into_iter().map(|name| (name.to_string(), name)),
+            2,
+        );
+
+        assert_eq!(suggestions, expected);
+    }
+}
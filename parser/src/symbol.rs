@@ -0,0 +1,224 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashSet, hash_map::DefaultHasher},
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Number of independently locked parts of the interner, so that parsers running in parallel
+/// seldom wait for each other
+const SHARDS: usize = 64;
+
+/// Every symbol created so far, shared by all parsers and spread over shards by hash
+///
+/// Symbols are not freed when dropped, the table only shrinks on [`Symbol::purge`]. It is bounded
+/// by the vocabulary of the pages parsed since, which is mostly the vocabulary of the manual
+/// itself as reparsing a page interns the same identifiers again
+static INTERNER: LazyLock<[Mutex<HashSet<Arc<str>>>; SHARDS]> =
+    LazyLock::new(|| std::array::from_fn(|_| Mutex::default()));
+
+/// The shard a symbol is interned in
+fn shard(value: &str) -> &'static Mutex<HashSet<Arc<str>>> {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+
+    &INTERNER[hasher.finish() as usize % SHARDS]
+}
+
+/// An interned string, for identifiers repeated all over the manual such as type names (`string`,
+/// `int`...), constant names and function references
+///
+/// Equal symbols share the same allocation, so cloning one is only a reference count increment.
+/// It is (de)serialized as a plain string.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(Arc<str>);
+
+impl Symbol {
+    pub fn new(value: &str) -> Self {
+        let mut interner = shard(value)
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if let Some(symbol) = interner.get(value) {
+            return Self(Arc::clone(symbol));
+        }
+
+        let symbol = Arc::<str>::from(value);
+        interner.insert(Arc::clone(&symbol));
+
+        Self(symbol)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Frees the symbols no longer used anywhere, e.g. once the pages using them were reparsed or
+    /// removed. Returns how many were freed
+    pub fn purge() -> usize {
+        INTERNER
+            .iter()
+            .map(|shard| {
+                // Symbols are only cloned out of the table under this lock, so one referenced by
+                // the table alone stays unused until it is removed
+                let mut interner = shard.lock().unwrap_or_else(|error| error.into_inner());
+                let length = interner.len();
+                interner.retain(|symbol| Arc::strong_count(symbol) > 1);
+
+                length - interner.len()
+            })
+            .sum()
+    }
+
+    /// Number of distinct symbols interned so far, along with the size of their contents in bytes
+    pub fn interned() -> (usize, usize) {
+        INTERNER
+            .iter()
+            .map(|shard| {
+                let interner = shard.lock().unwrap_or_else(|error| error.into_inner());
+
+                (
+                    interner.len(),
+                    interner.iter().map(|symbol| symbol.len()).sum::<usize>(),
+                )
+            })
+            .fold((0, 0), |(symbols, bytes), (shard_symbols, shard_bytes)| {
+                (symbols + shard_symbols, bytes + shard_bytes)
+            })
+    }
+}
+
+impl Default for Symbol {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Symbol {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(value: String) -> Self {
+        Self::new(&value)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Formatted as the string itself, so that debug output (and snapshots) do not change
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        Ok(Self::new(&value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{Symbol, shard};
+
+    #[test]
+    pub fn test_symbols_share_their_allocation() {
+        let left = Symbol::from("array");
+        let right = Symbol::from("array".to_string());
+
+        assert_eq!(left, right);
+        assert!(Arc::ptr_eq(&left.0, &right.0));
+        assert_eq!(format!("{left:?}"), format!("{:?}", "array"));
+    }
+
+    #[test]
+    pub fn test_purge() {
+        let is_interned = |value: &str| shard(value).lock().unwrap().contains(value);
+        let used = Symbol::from("test_purge_used");
+        drop(Symbol::from("test_purge_unused"));
+
+        Symbol::purge();
+        assert!(is_interned("test_purge_used"));
+        assert!(!is_interned("test_purge_unused"));
+        // Still shared with the symbols created afterwards
+        assert!(Arc::ptr_eq(&used.0, &Symbol::from("test_purge_used").0));
+    }
+
+    #[test]
+    pub fn test_interning_from_several_threads() {
+        let symbols = std::thread::scope(|scope| {
+            let workers = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        ["callable", "iterable", "callable"]
+                            .map(Symbol::from)
+                            .to_vec()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        for symbol in &symbols {
+            let first = symbols.iter().find(|other| *other == symbol).unwrap();
+            assert!(Arc::ptr_eq(&symbol.0, &first.0));
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::{manual::Manual, name::FullyQualifiedName};
+
+/// Words shorter than this hardly name a concept, e.g. `of` or `an`
+const MIN_KEYWORD_LENGTH: usize = 3;
+/// Words describing more functions than this are too common to name a concept, e.g. `get`
+const MAX_KEYWORD_FUNCTIONS: usize = 30;
+
+/// The names PHP gives to the same concept, to find `implode` when searching for `join` or `count`
+/// when searching for `length`
+///
+/// Functions are related to their aliases and to the functions they link to in their "See Also"
+/// section when those link back to them, and words to the functions whose short description
+/// contains them
+#[derive(Debug, Default)]
+pub struct Synonyms {
+    /// Functions related to a function, by lowercased fully qualified name
+    related: HashMap<String, BTreeSet<FullyQualifiedName>>,
+    /// Functions whose short description contains a word, by lowercased word
+    keywords: HashMap<String, BTreeSet<FullyQualifiedName>>,
+}
+
+impl Synonyms {
+    pub fn new(manual: &Manual) -> Self {
+        let mut synonyms = Self::default();
+
+        for (alias, target) in manual.aliases() {
+            synonyms.relate(alias, target);
+        }
+
+        for function in manual.functions() {
+            for word in keywords(&function.short_description) {
+                synonyms
+                    .keywords
+                    .entry(word)
+                    .or_default()
+                    .insert(function.name.clone());
+            }
+
+            // Pages also link to loosely related functions, which do not link back
+            let name = key(&function.name);
+            for linked in &function.see_also {
+                if manual.function(&linked.to_string()).is_some_and(|linked| {
+                    linked.see_also.iter().any(|backlink| key(backlink) == name)
+                }) {
+                    synonyms.relate(&function.name, linked);
+                }
+            }
+        }
+
+        synonyms
+    }
+
+    fn relate(&mut self, a: &FullyQualifiedName, b: &FullyQualifiedName) {
+        self.related.entry(key(a)).or_default().insert(b.clone());
+        self.related.entry(key(b)).or_default().insert(a.clone());
+    }
+
+    /// Names related to `word`, sorted, leaving out `word` itself: the functions related to it
+    /// if it is the name of a function, and the functions described by it along with those related
+    /// to them
+    ///
+    /// Nothing is related to several words, which describe more than a concept
+    pub fn expand(&self, word: &str) -> Vec<FullyQualifiedName> {
+        let word = word.trim().trim_start_matches('\\').to_lowercase();
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            return Vec::new();
+        }
+
+        let related = |name: &str| self.related.get(name).into_iter().flatten().cloned();
+
+        let mut names = related(&word).collect::<BTreeSet<_>>();
+        if let Some(described) = self
+            .keywords
+            .get(&word)
+            .filter(|described| described.len() <= MAX_KEYWORD_FUNCTIONS)
+        {
+            for name in described {
+                names.insert(name.clone());
+                names.extend(related(&key(name)));
+            }
+        }
+
+        names.into_iter().filter(|name| key(name) != word).collect()
+    }
+}
+
+/// Names are case-insensitive
+fn key(name: &FullyQualifiedName) -> String {
+    name.to_string().to_lowercase()
+}
+
+/// Lowercased words of a text, leaving out those too short to name a concept
+fn keywords(text: &str) -> impl Iterator<Item = String> {
+    text.split(|char: char| !char.is_alphanumeric() && char != '_')
+        .filter(|word| word.chars().count() >= MIN_KEYWORD_LENGTH)
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::Synonyms;
+    use crate::{
+        function::{Function, FunctionAlias, FunctionDefinition},
+        manual::{Manual, Page},
+        name::FullyQualifiedName,
+    };
+
+    fn function(name: &str, short_description: &str, see_also: &[&str]) -> Page {
+        FunctionDefinition::stub(name)
+            .described(short_description)
+            .see_also(see_also)
+            .page()
+    }
+
+    fn manual() -> Manual {
+        let mut manual = Manual::default();
+        for (name, page) in [
+            (
+                "strlen",
+                function("strlen", "Get string length", &["count", "mb_strlen"]),
+            ),
+            (
+                "mb-strlen",
+                function("mb_strlen", "Get string length", &["strlen"]),
+            ),
+            (
+                "count",
+                function(
+                    "count",
+                    "Counts all elements in an array or in a Countable object",
+                    &["is_array", "strlen"],
+                ),
+            ),
+            (
+                "implode",
+                function("implode", "Join array elements with a string", &[]),
+            ),
+            (
+                "join",
+                Page::Function(
+                    Function::Alias(FunctionAlias {
+                        name: FullyQualifiedName::from("join"),
+                        target: Some(FullyQualifiedName::from("implode")),
+                    }),
+                    Default::default(),
+                ),
+            ),
+            (
+                "str-contains",
+                function(
+                    "str_contains",
+                    "Determine if a string contains a given substring",
+                    &["strpos"],
+                ),
+            ),
+            (
+                "strpos",
+                function(
+                    "strpos",
+                    "Find the position of the first occurrence of a substring in a string",
+                    &[],
+                ),
+            ),
+        ] {
+            manual.insert(
+                PathBuf::from(format!("reference/strings/functions/{name}.xml")),
+                page,
+            );
+        }
+
+        manual
+    }
+
+    #[rstest::rstest]
+    #[case("length", &["count", "mb_strlen", "strlen"])]
+    #[case("LENGTH", &["count", "mb_strlen", "strlen"])]
+    #[case("join", &["implode"])]
+    #[case("implode", &["join"])]
+    #[case("strlen", &["count", "mb_strlen"])]
+    #[case("str_contains", &[])]
+    #[case("substring", &["str_contains", "strpos"])]
+    #[case("string length", &[])]
+    #[case("in", &[])]
+    pub fn test_expand(#[case] word: &str, #[case] expected: &[&str]) {
+        let synonyms = Synonyms::new(&manual());
+
+        assert_eq!(
+            synonyms
+                .expand(word)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
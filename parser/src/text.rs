@@ -1,21 +1,22 @@
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
-use crate::r#type::TypeHint;
+use crate::{symbol::Symbol, r#type::TypeHint};
 
-/// TODO: Intern some strings here (constants mostly)
 #[derive(Display, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TextNode {
     Text(String),
     BoldText(String),
     ItalicText(String),
     Subtitle(String),
-    Function(String),
-    Constant(String),
+    Function(Symbol),
+    Constant(Symbol),
     #[display("${_0}")]
     Parameter(String),
-    Classname(String),
-    InterfaceName(String),
+    /// `$_SERVER`, `$php_errormsg`...
+    Variable(String),
+    Classname(Symbol),
+    InterfaceName(Symbol),
     Literal(String),
     Filename(String),
     Type(TypeHint),
@@ -27,7 +28,7 @@ pub enum TextNode {
     InlineCode(String),
     InlinePhpCode(String),
     /// Countable::count
-    MethodName(String),
+    MethodName(Symbol),
     /// TODO: actually implement this
     Table(String),
     Xref(String),
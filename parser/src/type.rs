@@ -1,11 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-use libxml::tree::Node;
+use crate::{dom::Element, symbol::Symbol};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TypeHint {
-    Regular(String),
+    Regular(Symbol),
     Union(UnionTypeHint),
 }
 
@@ -26,43 +26,63 @@ pub struct UnionTypeHint {
     right: Box<TypeHint>,
 }
 
+impl TypeHint {
+    /// The union of the given types, or the type itself if there is only one
+    pub fn union(members: impl IntoIterator<Item = TypeHint>) -> Self {
+        let mut members = members.into_iter().collect::<Vec<_>>();
+        let last = members.pop().unwrap_or_default();
+
+        members.into_iter().rev().fold(last, |right, left| {
+            TypeHint::Union(UnionTypeHint {
+                left: Box::new(left),
+                right: Box::new(right),
+            })
+        })
+    }
+
+    /// The types of a union type, or the type itself, e.g. `[array, false]` for `array|false`
+    pub fn members(&self) -> Vec<&Symbol> {
+        match self {
+            TypeHint::Regular(regular) => vec![regular],
+            TypeHint::Union(union_type_hint) => {
+                let mut members = union_type_hint.left.members();
+                members.extend(union_type_hint.right.members());
+
+                members
+            }
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl TypeHint {
+    /// Reads a type as written in PHP, e.g. `?int` or `array|false`, for tests
+    pub fn stub(r#type: &str) -> Self {
+        match r#type.strip_prefix('?') {
+            Some(r#type) => Self::union([Self::stub(r#type), Self::stub("null")]),
+            None => Self::union(
+                r#type
+                    .split('|')
+                    .map(|member| TypeHint::Regular(member.into())),
+            ),
+        }
+    }
+}
+
 impl Default for TypeHint {
     fn default() -> Self {
-        Self::Regular("mixed".to_string())
+        Self::Regular("mixed".into())
     }
 }
 
-impl From<Node> for TypeHint {
-    fn from(value: Node) -> Self {
-        let children = value.get_child_elements();
+impl From<&Element> for TypeHint {
+    fn from(value: &Element) -> Self {
+        let children = value.child_elements().collect::<Vec<_>>();
         if children.is_empty() {
-            return TypeHint::Regular(value.get_content());
+            return TypeHint::Regular(value.content().into());
         }
 
         // Assuming we are working with a union type
-        let mut first_type = Option::<TypeHint>::None;
-        let mut union_type = Option::<UnionTypeHint>::None;
-        for child in children {
-            let type_hint = Self::from(child);
-
-            if let Some(first_type) = &first_type {
-                if let Some(union_type) = &mut union_type {
-                    let right_type = union_type.right.clone();
-                    union_type.right = Box::new(TypeHint::Union(UnionTypeHint {
-                        left: right_type,
-                        right: Box::new(type_hint),
-                    }));
-                } else {
-                    union_type = Some(UnionTypeHint {
-                        left: Box::new(first_type.clone()),
-                        right: Box::new(type_hint),
-                    });
-                }
-            } else {
-                first_type = Some(type_hint);
-            }
-        }
-
-        TypeHint::Union(union_type.unwrap())
+        Self::union(children.into_iter().map(Self::from))
     }
 }
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use super::text::TextNode;
+
+/// A predefined variable or superglobal, e.g. `$_SERVER`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PredefinedVariable {
+    pub name: String,
+    pub short_description: String,
+    pub description: Vec<TextNode>,
+    /// Documented array keys, e.g. `REQUEST_URI` for `$_SERVER`
+    pub keys: Vec<VariableKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct VariableKey {
+    pub name: String,
+    pub description: Vec<TextNode>,
+}
+
+impl PredefinedVariable {
+    /// How a key is accessed, e.g. `$_SERVER['REQUEST_URI']`
+    pub fn key_access(&self, key: &VariableKey) -> String {
+        // Some pages document the full access expression, e.g. `$_FILES['userfile']['name']`
+        if key.name.starts_with('$') {
+            return key.name.clone();
+        }
+
+        format!("{}['{}']", self.name, key.name)
+    }
+}
@@ -0,0 +1,99 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A PHP version, e.g. `8.1.0`
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct PhpVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+impl fmt::Display for PhpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for PhpVersion {
+    type Err = ();
+
+    /// Parses `8`, `8.1` or `8.1.2`, ignoring suffixes such as `RC1`
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let mut components = version.trim().split('.').map(|component| {
+            let digits = component
+                .find(|char: char| !char.is_ascii_digit())
+                .map_or(component, |end| &component[..end]);
+
+            digits.parse::<u16>().ok()
+        });
+
+        let major = components.next().flatten().ok_or(())?;
+        let minor = components.next().flatten().unwrap_or_default();
+        let patch = components.next().flatten().unwrap_or_default();
+
+        Ok(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+/// The PHP versions a function, class or method is available in, from the `versions.xml` of its
+/// extension
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Availability {
+    /// e.g. `mb_strlen`, `Random\Randomizer` or `Random\Randomizer::getInt`
+    pub name: String,
+    /// e.g. `PHP 4 >= 4.0.6, PHP 5, PHP 7, PHP 8`
+    pub from: String,
+}
+
+impl Availability {
+    /// The first version of PHP the entity is available in, `None` if it is only available from
+    /// PECL
+    pub fn since(&self) -> Option<PhpVersion> {
+        let first_release = self.from.split(',').next()?.trim().strip_prefix("PHP ")?;
+
+        match first_release.split_once(">=") {
+            Some((_, version)) => version.parse().ok(),
+            None => first_release.parse().ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Availability, PhpVersion};
+
+    #[rstest::rstest]
+    #[case("PHP 4, PHP 5, PHP 7, PHP 8", Some("4.0.0"))]
+    #[case("PHP 4 >= 4.0.6, PHP 5, PHP 7, PHP 8", Some("4.0.6"))]
+    #[case("PHP 8 >= 8.2.0", Some("8.2.0"))]
+    #[case("PHP 5 >= 5.3.0, PHP 7, PHP 8, PECL intl >= 1.0.0", Some("5.3.0"))]
+    #[case("PECL pecl_http >= 0.1.0", None)]
+    pub fn test_since(#[case] from: &str, #[case] since: Option<&str>) {
+        let availability = Availability {
+            name: "strlen".to_string(),
+            from: from.to_string(),
+        };
+
+        assert_eq!(
+            availability.since(),
+            since.map(|since| since.parse::<PhpVersion>().unwrap())
+        );
+    }
+
+    #[test]
+    pub fn test_version_ordering() {
+        let version = |version: &str| version.parse::<PhpVersion>().unwrap();
+
+        assert!(version("8.1") > version("8.0.30"));
+        assert!(version("8.0.0RC1") == version("8"));
+        assert!("php".parse::<PhpVersion>().is_err());
+    }
+}
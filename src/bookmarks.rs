@@ -0,0 +1,55 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{history::Entry, state_file};
+
+/// Starred entries, persisted in the user data directory between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bookmarks {
+    /// In the order they were starred
+    entries: Vec<Entry>,
+}
+
+impl Bookmarks {
+    const FILE_NAME: &str = "bookmarks.bin";
+
+    /// Loads the bookmarks from disk, starting without any if they are missing or unreadable
+    pub fn load() -> Self {
+        state_file::load(Self::FILE_NAME)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        state_file::save(Self::FILE_NAME, self)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, position: usize) -> Option<&Entry> {
+        self.entries.get(position)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, entry: &Entry) -> bool {
+        self.entries.contains(entry)
+    }
+
+    /// Stars the entry, or unstars it if it was starred
+    pub fn toggle(&mut self, entry: Entry) {
+        match self.entries.iter().position(|other| *other == entry) {
+            Some(position) => {
+                self.entries.remove(position);
+            }
+            None => self.entries.push(entry),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, Metadata},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use php_docbook_parser::{PARSER_VERSION, manual::Page};
+
+/// Identifies a version of a source file, a cached page is reused as long as it matches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    size: u64,
+    modified: SystemTime,
+}
+
+impl FileStamp {
+    fn new(metadata: &Metadata) -> Option<Self> {
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    stamp: FileStamp,
+    page: Page,
+}
+
+/// The [`IndexCache`] of a run, filled by the loader and kept up to date by the watcher
+///
+/// `None` until the loader read it from disk, and for runs which do not use the cache
+pub type SharedCache = Arc<Mutex<Option<IndexCache>>>;
+
+/// Runs `update` on the shared cache, unless there is none yet
+pub fn update_shared<T>(
+    cache: &SharedCache,
+    update: impl FnOnce(&mut IndexCache) -> T,
+) -> Option<T> {
+    cache
+        .lock()
+        .as_deref_mut()
+        .ok()
+        .and_then(Option::as_mut)
+        .map(update)
+}
+
+/// Parsed pages persisted in the user cache directory between runs, keyed by their absolute path
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexCache {
+    parser_version: u32,
+    entries: HashMap<PathBuf, CacheEntry>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl Default for IndexCache {
+    fn default() -> Self {
+        Self {
+            parser_version: PARSER_VERSION,
+            entries: HashMap::new(),
+            dirty: false,
+        }
+    }
+}
+
+impl IndexCache {
+    fn path() -> Option<PathBuf> {
+        ProjectDirs::from("dev", "Zuruuh", "php-docbook")
+            .map(|directories| directories.cache_dir().join("index.bin"))
+    }
+
+    /// Loads the cache from disk, starting from an empty one if it is missing, unreadable or was
+    /// written by another version of the parser
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| bincode::deserialize::<Self>(&bytes).ok())
+            .filter(|cache| cache.parser_version == PARSER_VERSION)
+            .unwrap_or_default()
+    }
+
+    /// The cached page for this file, if it did not change since it was cached
+    pub fn get(&self, path: &Path, metadata: &Metadata) -> Option<&Page> {
+        let entry = self.entries.get(path)?;
+
+        (Some(&entry.stamp) == FileStamp::new(metadata).as_ref()).then_some(&entry.page)
+    }
+
+    pub fn insert(&mut self, path: PathBuf, metadata: &Metadata, page: Page) {
+        let Some(stamp) = FileStamp::new(metadata) else {
+            return;
+        };
+
+        self.entries.insert(path, CacheEntry { stamp, page });
+        self.dirty = true;
+    }
+
+    /// Forgets the page of a file which was removed
+    pub fn remove(&mut self, path: &Path) {
+        self.dirty |= self.entries.remove(path).is_some();
+    }
+
+    /// Forgets the pages of files that no longer exist
+    pub fn retain(&mut self, paths: &HashSet<PathBuf>) {
+        let length = self.entries.len();
+        self.entries.retain(|path, _| paths.contains(path));
+        self.dirty |= self.entries.len() != length;
+    }
+
+    /// Writes the cache to disk if it changed since it was loaded
+    pub fn save(&mut self) -> io::Result<()> {
+        let Some(path) = Self::path().filter(|_| self.dirty) else {
+            return Ok(());
+        };
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        let bytes = bincode::serialize(self).map_err(io::Error::other)?;
+
+        // Write to a temporary file first so that an interrupted run never leaves a truncated cache
+        let temporary_path = path.with_extension("bin.tmp");
+        fs::write(&temporary_path, bytes)?;
+        fs::rename(temporary_path, path)?;
+        self.dirty = false;
+
+        Ok(())
+    }
+}
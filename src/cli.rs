@@ -2,7 +2,15 @@ use fancy_regex::{Captures, Regex};
 
 pub async fn replace_entities_i_hate_my_life() -> std::io::Result<()> {
     let regex = Regex::new("&(?!(amp|quot|gt|lt)\\b)([a-z]+);").unwrap();
-    for file in glob::glob("./.data/**/functions/**/*.xml").unwrap() {
+    let files = [
+        "./.data/**/functions/**/*.xml",
+        "./.data/language/**/*.xml",
+        "./.data/features/**/*.xml",
+    ]
+    .into_iter()
+    .flat_map(|pattern| glob::glob(pattern).unwrap());
+
+    for file in files {
         let file = file.unwrap();
         let file_content = tokio::fs::read_to_string(&file).await?;
         let replaced_content = regex.replace_all(&file_content, |e: &Captures| {
@@ -0,0 +1,107 @@
+use std::io;
+
+use php_docbook_parser::manual::Manual;
+use serde::{Deserialize, Serialize};
+
+use crate::state_file;
+
+/// Searches kept, older ones are forgotten first
+const MAX_SEARCHES: usize = 100;
+/// Opened entries kept, older ones are forgotten first
+const MAX_OPENED: usize = 20;
+
+/// What an entry is, to look for it in the right place
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryKind {
+    Function,
+    Variable,
+    Class,
+    /// A section of the language reference
+    Page,
+    Method,
+    Constant,
+    /// A `php.ini` directive
+    IniDirective,
+}
+
+/// Something documented, saved by name to find it again in a later run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub kind: EntryKind,
+    /// e.g. `str_contains`, `$_SERVER['REQUEST_URI']` or the key of a page, see
+    /// [`Section::key`](php_docbook_parser::chapter::Section::key)
+    pub name: String,
+}
+
+impl Entry {
+    /// The name shown for the entry, the title of pages
+    pub fn title<'a>(&'a self, manual: &'a Manual) -> &'a str {
+        match self.kind {
+            EntryKind::Page => manual
+                .section(&self.name)
+                .map_or(self.name.as_str(), |section| section.title.as_str()),
+            EntryKind::Function
+            | EntryKind::Variable
+            | EntryKind::Class
+            | EntryKind::Method
+            | EntryKind::Constant
+            | EntryKind::IniDirective => &self.name,
+        }
+    }
+}
+
+/// Searches and opened entries, persisted in the user data directory between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    /// The latest last
+    searches: Vec<String>,
+    /// The latest last
+    opened: Vec<Entry>,
+}
+
+impl History {
+    const FILE_NAME: &str = "history.bin";
+
+    /// Loads the history from disk, starting from an empty one if it is missing or unreadable
+    pub fn load() -> Self {
+        state_file::load(Self::FILE_NAME)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        state_file::save(Self::FILE_NAME, self)
+    }
+
+    /// Searches, the latest first
+    pub fn searches(&self) -> impl Iterator<Item = &str> {
+        self.searches.iter().rev().map(String::as_str)
+    }
+
+    /// Opened entries, the latest first
+    pub fn opened(&self) -> impl Iterator<Item = &Entry> {
+        self.opened.iter().rev()
+    }
+
+    /// Records a search, moving it first if it was made before
+    pub fn record_search(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+
+        record(&mut self.searches, query.to_string(), MAX_SEARCHES);
+    }
+
+    /// Records an opened entry, moving it first if it was opened before
+    pub fn record_opened(&mut self, entry: Entry) {
+        record(&mut self.opened, entry, MAX_OPENED);
+    }
+}
+
+fn record<T: PartialEq>(items: &mut Vec<T>, item: T, max: usize) {
+    items.retain(|other| *other != item);
+    items.push(item);
+
+    if items.len() > max {
+        items.drain(..items.len() - max);
+    }
+}
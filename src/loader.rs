@@ -0,0 +1,199 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::Metadata,
+    num::NonZeroUsize,
+    path::PathBuf,
+};
+
+use futures_util::StreamExt;
+use tokio::sync::mpsc;
+
+use php_docbook_parser::{
+    manual::{ManualOptions, Page, PageError, PageKind},
+    stream::{ParseStream, ParsedFile},
+};
+
+use crate::cache::{IndexCache, SharedCache, update_shared};
+
+/// Emitted by [`load_corpus`] for every file, in the order they finish loading
+#[derive(Debug)]
+pub enum ParseEvent {
+    Parsed(PathBuf, Page),
+    Failed(PathBuf, PageError),
+    /// Every file has been loaded
+    Done,
+    /// A file was saved or added while watching the checkout, see [`crate::watcher`]
+    Reparsed(PathBuf, Result<Page, PageError>),
+    /// A file was removed while watching the checkout
+    Removed(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LoadOptions {
+    /// Number of files parsed in parallel
+    pub jobs: NonZeroUsize,
+    /// Whether pages cached by previous runs are reused, and the cache updated
+    pub use_cache: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            jobs: ManualOptions::default().jobs,
+            use_cache: true,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub loaded: usize,
+    pub failed: usize,
+    pub total: usize,
+    pub done: bool,
+}
+
+impl LoadProgress {
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub fn record(&mut self, event: &ParseEvent) {
+        match event {
+            ParseEvent::Parsed(..) => self.loaded += 1,
+            ParseEvent::Failed(..) => self.failed += 1,
+            ParseEvent::Done => self.done = true,
+            // Only the initial load is tracked
+            ParseEvent::Reparsed(..) | ParseEvent::Removed(_) => {}
+        }
+    }
+}
+
+/// Runs [`load_corpus`] in the background, finishing the load if it panics so that the progress
+/// does not wait for it forever
+pub fn spawn_load(
+    files: Vec<(PageKind, PathBuf)>,
+    options: LoadOptions,
+    cache: SharedCache,
+    events: mpsc::UnboundedSender<ParseEvent>,
+) {
+    let loader = tokio::spawn(load_corpus(files, options, cache, events.clone()));
+
+    tokio::spawn(async move {
+        if let Err(error) = loader.await {
+            tracing::error!("The loader stopped before loading every file: {error}");
+
+            let _ = events.send(ParseEvent::Done);
+        }
+    });
+}
+
+/// Parses every file with a [`ParseStream`], reusing the pages cached by a previous run for
+/// the files which did not change since, and streams the results as [`ParseEvent`]s
+///
+/// The cache is read into `shared_cache` when the load starts and written back once it is done
+async fn load_corpus(
+    files: Vec<(PageKind, PathBuf)>,
+    options: LoadOptions,
+    shared_cache: SharedCache,
+    events: mpsc::UnboundedSender<ParseEvent>,
+) {
+    // Reading the cache and stat-ing every file is blocking too
+    let Ok((cache, cached_pages, queue, seen_paths)) = tokio::task::spawn_blocking(move || {
+        let cache = if options.use_cache {
+            IndexCache::load()
+        } else {
+            IndexCache::default()
+        };
+
+        let mut cached_pages = Vec::<(PathBuf, Page)>::new();
+        let mut queue = Vec::<(PageKind, PathBuf, Option<Metadata>)>::new();
+        let mut seen_paths = HashSet::<PathBuf>::new();
+
+        for (kind, path) in files {
+            let path = std::fs::canonicalize(&path).unwrap_or(path);
+            let metadata = std::fs::metadata(&path).ok();
+            seen_paths.insert(path.clone());
+
+            match metadata
+                .as_ref()
+                .and_then(|metadata| cache.get(&path, metadata))
+            {
+                Some(page) => cached_pages.push((path, page.clone())),
+                None => queue.push((kind, path, metadata)),
+            }
+        }
+
+        (cache, cached_pages, queue, seen_paths)
+    })
+    .await
+    else {
+        let _ = events.send(ParseEvent::Done);
+        return;
+    };
+
+    if options.use_cache {
+        if let Ok(mut shared_cache) = shared_cache.lock() {
+            *shared_cache = Some(cache);
+        }
+    }
+
+    for (path, page) in cached_pages {
+        let _ = events.send(ParseEvent::Parsed(path, page));
+    }
+
+    let (files, mut metadata): (Vec<_>, HashMap<_, _>) = queue
+        .into_iter()
+        .map(|(kind, path, metadata)| ((kind, path.clone()), (path, metadata)))
+        .unzip();
+    let mut stream = ParseStream::new(files, ManualOptions { jobs: options.jobs });
+
+    while let Some(ParsedFile { path, result, .. }) = stream.next().await {
+        let event = match result {
+            Ok(page) => {
+                if let Some(metadata) = metadata.remove(&path).flatten() {
+                    update_shared(&shared_cache, |cache| {
+                        cache.insert(path.clone(), &metadata, page.clone())
+                    });
+                }
+
+                ParseEvent::Parsed(path, page)
+            }
+            Err(error) => {
+                tracing::warn!("Could not parse {}: {error}", path.display());
+
+                ParseEvent::Failed(path, error)
+            }
+        };
+
+        // The receiving end is gone, e.g. the user quit while loading, dropping the stream stops
+        // the parsing
+        if events.send(event).is_err() {
+            return;
+        }
+    }
+
+    let _ = events.send(ParseEvent::Done);
+
+    if !options.use_cache {
+        return;
+    }
+
+    let saved = tokio::task::spawn_blocking(move || {
+        update_shared(&shared_cache, |cache| {
+            cache.retain(&seen_paths);
+            cache.save()
+        })
+    })
+    .await;
+    if let Ok(Some(Err(error))) = saved {
+        tracing::warn!("Could not write the index cache: {error}");
+    }
+}
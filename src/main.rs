@@ -1,15 +1,33 @@
-use std::sync::Arc;
+use std::{num::NonZeroUsize, path::Path, sync::Arc, time::Instant};
 
 use clap::Parser;
 use color_eyre::Result;
-use futures_util::lock::Mutex;
-
-use self::{cli::replace_entities_i_hate_my_life, tui::TerminalState};
-
-use php_docbook_parser::{XmlError, XmlParser, function::Function};
-
+use tokio::sync::mpsc;
+
+use self::{
+    bookmarks::Bookmarks,
+    cache::SharedCache,
+    cli::replace_entities_i_hate_my_life,
+    history::History,
+    loader::{LoadOptions, LoadProgress, ParseEvent, spawn_load},
+    tui::TerminalState,
+};
+
+use php_docbook_parser::{
+    XmlError, XmlParser,
+    function::Function,
+    manual::{Entity, Manual, PageKind},
+    symbol::Symbol,
+};
+
+mod bookmarks;
+mod cache;
 mod cli;
+mod history;
+mod loader;
+mod state_file;
 mod tui;
+mod watcher;
 
 #[derive(Default, clap::Subcommand)]
 pub enum Subcommand {
@@ -20,6 +38,17 @@ pub enum Subcommand {
     Start,
     /// Test command for development
     WIP,
+    /// Parse the whole documentation without entering the terminal UI, and report how long it took
+    Index {
+        /// Ignore and do not update the pages cached by previous runs
+        #[arg(long)]
+        no_cache: bool,
+    },
+    /// Print the synopsis of a function or class, or the closest names if it is not documented
+    Lookup {
+        /// e.g. `str_contains` or `Random\Randomizer`
+        name: String,
+    },
 }
 
 pub fn get_styles() -> clap::builder::Styles {
@@ -50,12 +79,45 @@ pub fn get_styles() -> clap::builder::Styles {
 pub struct CliArguments {
     #[clap(subcommand)]
     subcommand: Option<Subcommand>,
+    /// Number of files parsed in parallel [default: number of CPUs]
+    #[arg(short, long, global = true)]
+    jobs: Option<NonZeroUsize>,
+    /// Reload pages when their source file is saved, added or removed, in the terminal UI
+    #[arg(short, long, global = true)]
+    watch: bool,
+}
+
+/// Number of names suggested by `lookup` for names which are not documented
+const SUGGESTION_LIMIT: usize = 5;
+
+/// Parses the whole documentation, along with how many files were loaded
+async fn load_manual(load_options: LoadOptions) -> Result<(Manual, LoadProgress)> {
+    let files = PageKind::discover(Path::new("./.data"))?;
+    let mut progress = LoadProgress::new(files.len());
+    let mut manual = Manual::default();
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+
+    spawn_load(files, load_options, SharedCache::default(), sender);
+    while let Some(event) = receiver.recv().await {
+        progress.record(&event);
+        match event {
+            ParseEvent::Parsed(path, page) => manual.insert(path, page),
+            ParseEvent::Done => break,
+            ParseEvent::Failed(..) | ParseEvent::Reparsed(..) | ParseEvent::Removed(_) => {}
+        }
+    }
+
+    Ok((manual, progress))
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
     let cli_args = CliArguments::parse();
+    let mut load_options = LoadOptions::default();
+    if let Some(jobs) = cli_args.jobs {
+        load_options.jobs = jobs;
+    }
 
     match cli_args.subcommand.unwrap_or_default() {
         Subcommand::Setup => {
@@ -81,52 +143,94 @@ async fn main() -> Result<()> {
 
             Ok(())
         }
-        Subcommand::Start => {
-            let terminal = ratatui::init();
+        Subcommand::Index { no_cache } => {
+            load_options.use_cache = !no_cache;
+
+            let start = Instant::now();
+            let (manual, progress) = load_manual(load_options).await?;
+            let elapsed = start.elapsed();
+
+            println!(
+                "Loaded {}/{} files ({} failed) in {elapsed:.2?} with {} jobs: {} functions, {} classes, {} chapters, {} variables, {} methods, {} constants, {} ini directives",
+                progress.loaded,
+                progress.total,
+                progress.failed,
+                load_options.jobs,
+                manual.functions().count(),
+                manual.classes().count(),
+                manual.chapters().count(),
+                manual.variables().count(),
+                manual.methods().count(),
+                manual.constants().count(),
+                manual.ini_directives().count(),
+            );
+
+            let (symbols, symbol_bytes) = Symbol::interned();
+            println!("Interned {symbols} distinct identifiers ({symbol_bytes} bytes)");
 
-            let files =
-                glob::glob("./.data/**/functions/**/*.xml")?.collect::<Result<Vec<_>, _>>()?;
+            Ok(())
+        }
+        Subcommand::Lookup { name } => {
+            let (manual, _) = load_manual(load_options).await?;
+
+            if let Some(function) = manual.function(&name) {
+                println!("{function}\n\n{}", function.short_description);
+
+                return Ok(());
+            }
+            if let Some(class) = manual.class(&name) {
+                println!("{class}");
+
+                return Ok(());
+            }
+            if let Some(method) = manual.method(&name) {
+                println!("{method}\n\n{}", method.short_description);
+
+                return Ok(());
+            }
+
+            eprintln!("No function, class or method is named {name}");
+            let suggestions = manual.did_you_mean(&name, SUGGESTION_LIMIT);
+            if !suggestions.is_empty() {
+                eprintln!("\nDid you mean:");
+                for entity in suggestions {
+                    match entity {
+                        Entity::Function(function) | Entity::Method(function) => {
+                            eprintln!("  {}", function.name)
+                        }
+                        Entity::Class(class) => eprintln!("  {}", class.name),
+                        Entity::Chapter(_)
+                        | Entity::Variable(_)
+                        | Entity::Constant(_)
+                        | Entity::IniDirective(_) => {}
+                    }
+                }
+            }
+
+            std::process::exit(1);
+        }
+        Subcommand::Start => {
+            // Anything which can fail runs before the terminal is switched to raw mode, an error
+            // would leave it that way
+            let files = PageKind::discover(Path::new("./.data"))?;
+
+            let cache = SharedCache::default();
+            let (sender, receiver) = mpsc::unbounded_channel();
+            // Kept until the terminal UI quits, as dropping it stops watching
+            let _watcher = cli_args
+                .watch
+                .then(|| watcher::watch(Path::new("./.data"), cache.clone(), sender.clone()))
+                .transpose()?;
 
             let mut state = TerminalState::default();
-            state.shared_state.total_files_to_parse = files.len();
+            state.shared_state.loading_progress = LoadProgress::new(files.len());
+            state.shared_state.history = History::load();
+            state.shared_state.bookmarks = Bookmarks::load();
 
-            let parsed_files = Arc::new(Mutex::new(Vec::<Function>::new()));
-            let xml_parser = Arc::new(XmlParser::default());
-            let parsed_files_arc_for_tokio_task = Arc::clone(&parsed_files);
-
-            tokio::spawn(async move {
-                let futures = files
-                    .into_iter()
-                    .map(async |filepath| -> Result<(), XmlError> {
-                        let file_content = std::fs::read(filepath).map_err(XmlError::IOError)?;
-                        let result = Arc::clone(&xml_parser).parse_function(file_content)?;
-
-                        Arc::clone(&parsed_files_arc_for_tokio_task)
-                            .lock()
-                            .await
-                            .push(result);
-
-                        Ok(())
-                    })
-                    .collect::<Vec<_>>();
-
-                futures_util::future::join_all(futures).await;
-            });
-
-            let result = state
-                .run(
-                    terminal,
-                    Box::new(async move |state: &mut TerminalState| {
-                        let parsed_files_mutex = Arc::clone(&parsed_files);
-                        let parsed_files = parsed_files_mutex.lock().await;
-
-                        if parsed_files.len() != state.shared_state.parsed_files_snapshot.len() {
-                            state.shared_state.parsed_files_snapshot =
-                                parsed_files.iter().cloned().collect()
-                        }
-                    }),
-                )
-                .await;
+            let terminal = ratatui::init();
+            spawn_load(files, load_options, cache, sender);
+
+            let result = state.run(terminal, receiver).await;
 
             ratatui::restore();
 
@@ -0,0 +1,37 @@
+use std::{fs, io, path::PathBuf};
+
+use directories::ProjectDirs;
+use serde::{Serialize, de::DeserializeOwned};
+
+/// A file of the user data directory, e.g. `history.bin`
+fn path(name: &str) -> Option<PathBuf> {
+    ProjectDirs::from("dev", "Zuruuh", "php-docbook")
+        .map(|directories| directories.data_dir().join(name))
+}
+
+/// Reads the state saved in the file `name` of the user data directory, the default one if it is
+/// missing or unreadable
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    path(name)
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|bytes| bincode::deserialize(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Writes the state to the file `name` of the user data directory
+pub fn save<T: Serialize>(name: &str, state: &T) -> io::Result<()> {
+    let Some(path) = path(name) else {
+        return Ok(());
+    };
+
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+
+    let bytes = bincode::serialize(state).map_err(io::Error::other)?;
+
+    // Write to a temporary file first so that an interrupted run never leaves a truncated file
+    let temporary_path = path.with_extension("tmp");
+    fs::write(&temporary_path, bytes)?;
+    fs::rename(temporary_path, path)
+}
@@ -1,10 +1,21 @@
-use color_eyre::Result;
+use color_eyre::{Result, eyre::eyre};
 use crossterm::event::{Event, KeyEventKind};
 use futures_util::{FutureExt, StreamExt};
+use tokio::time::{Duration, Instant};
+
+use crate::{
+    history::{Entry, EntryKind},
+    loader::ParseEvent,
+};
 
 use super::{
-    TerminalState,
-    modal::search_modal::{SearchModal, SearchModalType},
+    Screen, TerminalState,
+    modal::{
+        Modal,
+        search_engine::SearchResults,
+        search_modal::{SearchModal, SearchModalType},
+    },
+    screen::{HomeScreen, bookmarks_screen::BookmarksScreen, manual_screen::ManualScreen},
 };
 
 #[derive(Debug)]
@@ -16,14 +27,109 @@ pub(super) enum EventHandlerResult {
 
 #[derive(Debug)]
 pub enum Message {
-    OpenFunctionSearchModal,
+    OpenSearchModal(SearchModalType),
+    GoToHomeScreen,
+    GoToManualScreen,
+    /// Records a search in the history, along with the entry selected when it was submitted
+    RecordSearch {
+        query: String,
+        opened: Option<Entry>,
+    },
+    /// Searches again for one of the recently opened entries, the latest being `0`
+    OpenRecent(usize),
+    GoToBookmarksScreen,
+    /// Stars the entry, or unstars it if it was starred
+    ToggleBookmark(Entry),
+    /// Opens the bookmark at this position
+    OpenBookmark(usize),
+    /// Unstars the bookmark at this position
+    RemoveBookmark(usize),
+}
+
+impl TerminalState {
+    fn open_search_modal(&mut self, r#type: SearchModalType, query: &str) {
+        self.open_modal = Some(SearchModal::new(
+            r#type,
+            &self.shared_state.history,
+            query.to_string(),
+        ));
+    }
+
+    /// Searches for the entry in the modal of its kind, or browses the manual at its page
+    fn open_entry(&mut self, entry: &Entry) {
+        match entry.kind {
+            EntryKind::Function
+            | EntryKind::Class
+            | EntryKind::Variable
+            | EntryKind::Method
+            | EntryKind::Constant
+            | EntryKind::IniDirective => {
+                self.open_search_modal(SearchModalType::Only(entry.kind), &entry.name)
+            }
+            EntryKind::Page => {
+                self.open_modal = None;
+                self.screen = Screen::Manual(ManualScreen::at(&entry.name));
+            }
+        }
+    }
+
+    fn save_bookmarks(&self) {
+        if let Err(error) = self.shared_state.bookmarks.save() {
+            tracing::warn!("Could not write the bookmarks: {error}");
+        }
+    }
 }
 
 impl Message {
     pub async fn handle(&self, state: &mut TerminalState) {
         match self {
-            Message::OpenFunctionSearchModal => {
-                state.open_modal = Some(SearchModal::new(SearchModalType::Function));
+            Message::OpenSearchModal(r#type) => state.open_search_modal(*r#type, ""),
+            Message::GoToHomeScreen => {
+                state.screen = Screen::Home(HomeScreen);
+            }
+            Message::GoToManualScreen => {
+                state.screen = Screen::Manual(ManualScreen::default());
+            }
+            Message::RecordSearch { query, opened } => {
+                let history = &mut state.shared_state.history;
+                history.record_search(query);
+                if let Some(opened) = opened {
+                    history.record_opened(opened.clone());
+                }
+
+                if let Err(error) = history.save() {
+                    tracing::warn!("Could not write the search history: {error}");
+                }
+            }
+            Message::OpenRecent(position) => {
+                let Some(entry) = state.shared_state.history.opened().nth(*position).cloned()
+                else {
+                    return;
+                };
+
+                state.open_entry(&entry);
+            }
+            Message::GoToBookmarksScreen => {
+                state.screen = Screen::Bookmarks(BookmarksScreen::default());
+            }
+            Message::ToggleBookmark(entry) => {
+                state.shared_state.bookmarks.toggle(entry.clone());
+                state.save_bookmarks();
+            }
+            Message::OpenBookmark(position) => {
+                let Some(entry) = state.shared_state.bookmarks.get(*position).cloned() else {
+                    return;
+                };
+
+                state.open_entry(&entry);
+            }
+            Message::RemoveBookmark(position) => {
+                let Some(entry) = state.shared_state.bookmarks.get(*position).cloned() else {
+                    return;
+                };
+
+                state.shared_state.bookmarks.toggle(entry);
+                state.save_bookmarks();
             }
         }
     }
@@ -33,23 +139,107 @@ pub(super) trait EventHandler {
     async fn on_key_event(&mut self, key: &crossterm::event::KeyEvent) -> EventHandlerResult;
 }
 
-pub(super) trait CrosstermEventHandler {
-    async fn handle_crossterm_events(&mut self) -> Result<()>;
+/// Minimum delay between two frames redrawn because of parse events, which arrive by thousands
+const PARSE_REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Everything the main loop wakes up for
+#[derive(Debug)]
+pub(super) enum AppEvent {
+    Terminal(Event),
+    /// Reading the terminal failed, which the next read may not
+    TerminalError(std::io::Error),
+    Parse(ParseEvent),
+    /// Neither the loader nor the watcher will send parse events anymore
+    ParseEventsClosed,
+    /// A redraw was deferred to coalesce parse events, and is now due
+    DeferredRedraw,
+    /// The search of the open modal completed in the background
+    SearchResults(Box<SearchResults>),
 }
 
-impl CrosstermEventHandler for super::TerminalState {
-    async fn handle_crossterm_events(&mut self) -> Result<()> {
+pub(super) trait AppEventHandler {
+    /// Waits for the next event, without any timeout so that nothing runs while idle
+    async fn next_event(&mut self) -> Result<AppEvent>;
+    async fn handle_event(&mut self, event: AppEvent);
+}
+
+impl AppEventHandler for TerminalState {
+    async fn next_event(&mut self) -> Result<AppEvent> {
+        let parse_events = self.parse_events.as_mut();
+        let redraw_at = self.redraw_at;
+        let search_engine = self
+            .open_modal
+            .as_mut()
+            .map(|Modal::SearchModal(modal)| modal.engine_mut());
+
         tokio::select! {
-            event = self.event_stream.next().fuse() => {
-                if let Some(Ok(Event::Key(key))) = event {
-                    if key.kind == KeyEventKind::Press {
-                        self.on_key_event(key).await;
-                    }
+            event = self.event_stream.next().fuse() => match event {
+                Some(Ok(event)) => Ok(AppEvent::Terminal(event)),
+                Some(Err(error)) => Ok(AppEvent::TerminalError(error)),
+                None => Err(eyre!("The terminal event stream was closed")),
+            },
+            event = async {
+                match parse_events {
+                    Some(parse_events) => parse_events.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => Ok(match event {
+                Some(event) => AppEvent::Parse(event),
+                None => AppEvent::ParseEventsClosed,
+            }),
+            _ = async {
+                match redraw_at {
+                    Some(redraw_at) => tokio::time::sleep_until(redraw_at).await,
+                    None => std::future::pending().await,
+                }
+            } => Ok(AppEvent::DeferredRedraw),
+            results = async {
+                match search_engine {
+                    Some(search_engine) => search_engine.background_results().await,
+                    None => std::future::pending().await,
                 }
+            } => Ok(AppEvent::SearchResults(Box::new(results))),
+        }
+    }
+
+    async fn handle_event(&mut self, event: AppEvent) {
+        match event {
+            AppEvent::Terminal(Event::Key(key)) => {
+                if key.kind == KeyEventKind::Press {
+                    self.on_key_event(key).await;
+                    self.dirty = true;
+                }
+            }
+            AppEvent::Terminal(Event::Resize(..)) => self.dirty = true,
+            AppEvent::Terminal(_) => {}
+            AppEvent::TerminalError(error) => {
+                tracing::warn!("Could not read a terminal event: {error}");
+            }
+            AppEvent::Parse(ParseEvent::Done) => {
+                self.shared_state.apply(ParseEvent::Done);
+                self.dirty = true;
             }
-            _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {}
-        };
+            AppEvent::ParseEventsClosed => self.parse_events = None,
+            AppEvent::Parse(event) => {
+                self.shared_state.apply(event);
+                self.redraw_at
+                    .get_or_insert_with(|| Instant::now() + PARSE_REDRAW_INTERVAL);
+            }
+            AppEvent::DeferredRedraw => self.dirty = true,
+            AppEvent::SearchResults(results) => {
+                // The modal may have been closed since, along with its engine
+                if let Some(Modal::SearchModal(modal)) = &mut self.open_modal {
+                    modal.receive(*results);
+                    self.dirty = true;
+                }
+            }
+        }
 
-        Ok(())
+        // Parse events wait for the searches which held the manual, the last of which may have
+        // finished without its results being wanted anymore
+        if self.shared_state.apply_deferred() {
+            self.redraw_at
+                .get_or_insert_with(|| Instant::now() + PARSE_REDRAW_INTERVAL);
+        }
     }
 }
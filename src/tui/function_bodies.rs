@@ -0,0 +1,56 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use php_docbook_parser::{
+    XmlParser,
+    function::FunctionBody,
+    manual::{PageError, parse_function_body, parse_page_text},
+    search::Document,
+};
+
+/// Bodies and plain text of the functions displayed so far, parsed from their source file on
+/// first access
+#[derive(Default)]
+pub struct FunctionBodies {
+    xml_parser: XmlParser,
+    bodies: HashMap<PathBuf, Option<FunctionBody>>,
+    /// For the snippets of full-text search results, the manual only keeps their terms
+    texts: HashMap<PathBuf, Option<Document>>,
+}
+
+impl FunctionBodies {
+    /// The body of the function page at `path`, `None` if it could not be parsed
+    pub fn get(&mut self, path: &Path) -> Option<&FunctionBody> {
+        self.bodies
+            .entry(path.to_path_buf())
+            .or_insert_with(|| parsed(path, parse_function_body(&self.xml_parser, path)))
+            .as_ref()
+    }
+
+    /// The plain text of the function or method page at `path`, `None` if it could not be parsed
+    pub fn text(&mut self, path: &Path) -> Option<&Document> {
+        self.texts
+            .entry(path.to_path_buf())
+            .or_insert_with(|| parsed(path, parse_page_text(&self.xml_parser, path)))
+            .as_ref()
+    }
+
+    /// Drops what was parsed from `path`, so that it is parsed again after the file changed
+    pub fn forget(&mut self, path: &Path) {
+        self.bodies.remove(path);
+        self.texts.remove(path);
+    }
+}
+
+fn parsed<T>(path: &Path, result: Result<T, PageError>) -> Option<T> {
+    match result {
+        Ok(parsed) => Some(parsed),
+        Err(error) => {
+            tracing::warn!("Could not parse {}: {error}", path.display());
+
+            None
+        }
+    }
+}
@@ -1,17 +1,24 @@
-use std::collections::BTreeSet;
+use std::{path::PathBuf, sync::Arc};
 
 use color_eyre::Result;
 use crossterm::event::{EventStream, KeyCode, KeyEvent, KeyModifiers};
-use event::{CrosstermEventHandler, EventHandler, EventHandlerResult};
-use fuzzy_matcher::skim::SkimMatcherV2;
+use event::{AppEventHandler, EventHandler, EventHandlerResult};
+use function_bodies::FunctionBodies;
 use ratatui::{DefaultTerminal, prelude::*, widgets::Block};
-use screen::HomeScreen;
+use screen::{HomeScreen, bookmarks_screen::BookmarksScreen, manual_screen::ManualScreen};
+use tokio::{sync::mpsc, time::Instant};
 
 pub mod event;
+mod function_bodies;
 pub mod modal;
 pub mod screen;
 
-use php_docbook_parser::function::Function;
+use crate::{
+    bookmarks::Bookmarks,
+    history::History,
+    loader::{LoadProgress, ParseEvent},
+};
+use php_docbook_parser::{manual::Manual, symbol::Symbol};
 
 #[derive(Default)]
 pub struct TerminalState {
@@ -20,18 +27,135 @@ pub struct TerminalState {
     screen: Screen,
     open_modal: Option<modal::Modal>,
     pub shared_state: SharedState,
+    /// Parse events of the loader and of the watcher, until both are gone
+    parse_events: Option<mpsc::UnboundedReceiver<ParseEvent>>,
+    /// Whether something changed since the last frame
+    dirty: bool,
+    /// When the next frame is due, if one was deferred
+    redraw_at: Option<Instant>,
 }
 
 #[derive(Default)]
 pub struct SharedState {
-    pub parsed_files_snapshot: BTreeSet<Function>,
-    pub total_files_to_parse: usize,
-    pub fuzzy_matcher: SkimMatcherV2,
+    /// Shared with the searches running in the background, see [`SharedState::apply`]
+    pub manual: Arc<Manual>,
+    /// Parse events received while a search held the manual, in the order they were received
+    deferred_events: Vec<ParseEvent>,
+    pub function_bodies: FunctionBodies,
+    pub loading_progress: LoadProgress,
+    /// Files which could not be parsed, along with the reason why
+    pub parse_failures: Vec<(PathBuf, String)>,
+    /// The last file reloaded while watching the checkout
+    pub last_reload: Option<Reload>,
+    /// Searches and opened entries of this and previous runs
+    pub history: History,
+    pub bookmarks: Bookmarks,
+}
+
+impl SharedState {
+    /// Applies the event to the manual, or defers it until no search holds the manual anymore
+    pub fn apply(&mut self, event: ParseEvent) {
+        self.deferred_events.push(event);
+        self.apply_deferred();
+    }
+
+    /// Applies the events deferred while a search held the manual, if it is done with it.
+    /// Returns whether any was applied
+    pub fn apply_deferred(&mut self) -> bool {
+        if self.deferred_events.is_empty() {
+            return false;
+        }
+        // Searches only read the manual, which is updated in place rather than copied
+        let Some(manual) = Arc::get_mut(&mut self.manual) else {
+            return false;
+        };
+
+        let mut reloaded = false;
+        for event in self.deferred_events.drain(..) {
+            self.loading_progress.record(&event);
+            reloaded |= matches!(event, ParseEvent::Reparsed(..) | ParseEvent::Removed(_));
+
+            match event {
+                ParseEvent::Parsed(path, page) => manual.insert(path, page),
+                ParseEvent::Failed(path, error) => {
+                    self.parse_failures.push((path, error.to_string()))
+                }
+                ParseEvent::Done => {}
+                ParseEvent::Reparsed(path, result) => {
+                    self.function_bodies.forget(&path);
+                    self.parse_failures.retain(|(failed, _)| *failed != path);
+
+                    self.last_reload = Some(match result {
+                        Ok(page) => {
+                            manual.insert(path.clone(), page);
+
+                            Reload::Parsed(path)
+                        }
+                        // The page parsed before the file was saved is kept until it is fixed
+                        Err(error) => {
+                            self.parse_failures.push((path.clone(), error.to_string()));
+
+                            Reload::Failed(path, error.to_string())
+                        }
+                    });
+                }
+                ParseEvent::Removed(path) => {
+                    self.function_bodies.forget(&path);
+                    self.parse_failures.retain(|(failed, _)| *failed != path);
+                    manual.remove(&path);
+                    self.last_reload = Some(Reload::Removed(path));
+                }
+            }
+        }
+        // The identifiers of the pages replaced by a reload would stay interned for good
+        if reloaded {
+            Symbol::purge();
+        }
+
+        true
+    }
+}
+
+/// What became of a file which changed while watching the checkout
+#[derive(Debug)]
+pub enum Reload {
+    Parsed(PathBuf),
+    /// The file could not be parsed, along with the reason why
+    Failed(PathBuf, String),
+    Removed(PathBuf),
+}
+
+impl Reload {
+    fn to_line(&self) -> Line<'static> {
+        // Paths are absolute, the current directory is usually the one containing the checkout
+        let display = |path: &PathBuf| {
+            std::env::current_dir()
+                .ok()
+                .and_then(|current_dir| {
+                    let current_dir = std::fs::canonicalize(current_dir).ok()?;
+
+                    Some(path.strip_prefix(current_dir).ok()?.to_path_buf())
+                })
+                .unwrap_or_else(|| path.clone())
+                .display()
+                .to_string()
+        };
+
+        match self {
+            Reload::Parsed(path) => Line::from(format!("[Reloaded {}]", display(path))).green(),
+            Reload::Failed(path, error) => {
+                Line::from(format!("[Could not parse {}: {error}]", display(path))).red()
+            }
+            Reload::Removed(path) => Line::from(format!("[Removed {}]", display(path))).yellow(),
+        }
+    }
 }
 
 #[derive(Debug)]
 enum Screen {
     Home(HomeScreen),
+    Manual(ManualScreen),
+    Bookmarks(BookmarksScreen),
 }
 
 impl Default for Screen {
@@ -41,38 +165,55 @@ impl Default for Screen {
 }
 
 impl TerminalState {
-    pub async fn run<Callback>(
+    pub async fn run(
         mut self,
         mut terminal: DefaultTerminal,
-        mut update_callback: Callback,
-    ) -> Result<()>
-    where
-        Callback: AsyncFnMut(&mut TerminalState),
-    {
+        parse_events: mpsc::UnboundedReceiver<ParseEvent>,
+    ) -> Result<()> {
+        self.parse_events = Some(parse_events);
         self.running = true;
+        self.dirty = true;
         while self.running {
-            terminal.draw(|frame| self.draw(frame))?;
-            self.handle_crossterm_events().await?;
-            update_callback(&mut self).await;
+            if self.dirty {
+                self.search();
+                terminal.draw(|frame| self.draw(frame))?;
+                self.dirty = false;
+                self.redraw_at = None;
+            }
+
+            let event = self.next_event().await?;
+            self.handle_event(event).await;
         }
 
         Ok(())
     }
 
+    /// Matches the input of the open search modal against the manual, if either changed
+    fn search(&mut self) {
+        if let Some(modal::Modal::SearchModal(modal)) = &mut self.open_modal {
+            modal.search(&self.shared_state);
+        }
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         let area = frame.area();
-        let block = Block::bordered().title(
+        let mut block = Block::bordered().title(
             Line::from(format!("[PHP DocBook {}]", env!("CARGO_PKG_VERSION")))
                 .bold()
                 .blue()
                 .centered(),
         );
+        if let Some(reload) = &self.shared_state.last_reload {
+            block = block.title_bottom(reload.to_line().centered());
+        }
         let container = block.inner(area);
         let buf = frame.buffer_mut();
         block.render(area, buf);
 
         match &mut self.screen {
             Screen::Home(screen) => screen.render(container, buf, &mut self.shared_state),
+            Screen::Manual(screen) => screen.render(container, buf, &mut self.shared_state),
+            Screen::Bookmarks(screen) => screen.render(container, buf, &mut self.shared_state),
         };
 
         let modal = match self.open_modal.as_mut() {
@@ -113,6 +254,8 @@ impl TerminalState {
 
         let result = match &mut self.screen {
             Screen::Home(screen) => screen.on_key_event(&key).await,
+            Screen::Manual(screen) => screen.on_key_event(&key).await,
+            Screen::Bookmarks(screen) => screen.on_key_event(&key).await,
         };
         match result {
             EventHandlerResult::Handled => {
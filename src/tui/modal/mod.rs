@@ -8,6 +8,8 @@ use search_modal::SearchModal;
 
 use super::{EventHandler, EventHandlerResult, SharedState};
 
+mod ranking;
+pub mod search_engine;
 pub mod search_modal;
 
 #[derive(Debug)]
@@ -18,7 +20,7 @@ pub enum Modal {
 impl Modal {
     pub fn title(&self) -> &'static str {
         match self {
-            Modal::SearchModal { .. } => "Search functions",
+            Modal::SearchModal(_) => "Search",
         }
     }
 }
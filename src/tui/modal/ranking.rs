@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+
+use php_docbook_parser::name::FullyQualifiedName;
+
+use crate::history::{EntryKind, History};
+
+/// Added to the score of a name equal to the query, ignoring case
+const EXACT_MATCH_BOOST: i64 = 1000;
+/// Added to the score of a name starting with the query, ignoring case
+const PREFIX_BOOST: i64 = 200;
+/// Added for every matched character starting a word of the name, e.g. `r` in `str_replace`
+const WORD_BOUNDARY_BOOST: i64 = 15;
+/// Added to the score of the functions and methods opened recently, see [`frequently_used`]
+const FREQUENTLY_USED_BOOST: i64 = 40;
+
+/// Lowercased names of the functions and methods opened recently, likely what is looked for again
+/// among similar names
+pub fn frequently_used(history: &History) -> HashSet<String> {
+    history
+        .opened()
+        .filter(|entry| matches!(entry.kind, EntryKind::Function | EntryKind::Method))
+        .map(|entry| entry.name.to_lowercase())
+        .collect()
+}
+
+/// Scores how well a name matches the query, `None` if it does not. The indices of the matching
+/// characters are relative to the fully qualified name
+///
+/// The fuzzy score of the best match of either the short or the fully qualified name is boosted
+/// for exact matches, prefixes, matches at the start of words and frequently used functions and
+/// methods
+pub fn score(
+    matcher: &SkimMatcherV2,
+    name: &FullyQualifiedName,
+    query: &str,
+    frequently_used: bool,
+) -> Option<(i64, Vec<usize>)> {
+    let (mut score, indices) = match_name(matcher, name, query)?;
+
+    let qualified_name = name.to_string();
+    let short_name = name.name.to_lowercase();
+    let query = query.trim().trim_start_matches('\\').to_lowercase();
+    if !query.is_empty() {
+        if short_name == query || qualified_name.to_lowercase() == query {
+            score += EXACT_MATCH_BOOST;
+        } else if short_name.starts_with(&query) {
+            score += PREFIX_BOOST;
+        }
+    }
+
+    let qualified_name = qualified_name.chars().collect::<Vec<_>>();
+    let word_starts = indices
+        .iter()
+        .filter(|&&i| i == 0 || matches!(qualified_name.get(i - 1), Some('_' | '\\')))
+        .count() as i64;
+    score += word_starts * WORD_BOUNDARY_BOOST;
+
+    if frequently_used {
+        score += FREQUENTLY_USED_BOOST;
+    }
+
+    Some((score, indices))
+}
+
+/// Fuzzy matches the query against both the short and the fully qualified name, keeping the best
+/// match. Returned indices are relative to the fully qualified name.
+fn match_name(
+    matcher: &SkimMatcherV2,
+    name: &FullyQualifiedName,
+    query: &str,
+) -> Option<(i64, Vec<usize>)> {
+    let qualified_name = name.to_string();
+    let namespace_length = qualified_name.chars().count() - name.name.chars().count();
+
+    let short_match = matcher
+        .fuzzy_indices(name.name.as_str(), query)
+        .map(|(score, indices)| {
+            (
+                score,
+                indices
+                    .into_iter()
+                    .map(|i| i + namespace_length)
+                    .collect::<Vec<_>>(),
+            )
+        });
+    let qualified_match = name
+        .is_namespaced()
+        .then(|| matcher.fuzzy_indices(qualified_name.as_str(), query))
+        .flatten();
+
+    [short_match, qualified_match]
+        .into_iter()
+        .flatten()
+        .max_by_key(|(score, _)| *score)
+}
+
+#[cfg(test)]
+mod test {
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    use php_docbook_parser::name::FullyQualifiedName;
+
+    use super::{
+        EXACT_MATCH_BOOST, FREQUENTLY_USED_BOOST, PREFIX_BOOST, WORD_BOUNDARY_BOOST, match_name,
+        score,
+    };
+
+    #[rstest::rstest]
+    #[case("strlen", "strlen", Some(vec![0, 1, 2, 3, 4, 5]))]
+    #[case("str_replace", "srep", Some(vec![0, 4, 5, 6]))]
+    // Ties go to the fully qualified name
+    #[case("Random\\Randomizer", "rand", Some(vec![0, 1, 2, 3]))]
+    #[case("Random\\Randomizer", "mizer", Some(vec![12, 13, 14, 15, 16]))]
+    #[case("Random\\Randomizer", "r\\r", Some(vec![0, 6, 7]))]
+    #[case("strlen", "strpos", None)]
+    pub fn test_match_name(
+        #[case] name: &str,
+        #[case] query: &str,
+        #[case] indices: Option<Vec<usize>>,
+    ) {
+        let matcher = SkimMatcherV2::default();
+
+        assert_eq!(
+            match_name(&matcher, &FullyQualifiedName::from(name), query)
+                .map(|(_, indices)| indices),
+            indices
+        );
+    }
+
+    #[rstest::rstest]
+    #[case("strlen", "strlen", EXACT_MATCH_BOOST + WORD_BOUNDARY_BOOST)]
+    #[case("STRLEN", "strlen", EXACT_MATCH_BOOST + WORD_BOUNDARY_BOOST)]
+    #[case("strlen", "str", PREFIX_BOOST + WORD_BOUNDARY_BOOST)]
+    // `s` and `r` start the words of `str_replace`
+    #[case("str_replace", "srep", 2 * WORD_BOUNDARY_BOOST)]
+    #[case("Random\\Randomizer", "random\\randomizer", EXACT_MATCH_BOOST + 2 * WORD_BOUNDARY_BOOST)]
+    pub fn test_score_boosts(#[case] name: &str, #[case] query: &str, #[case] boost: i64) {
+        let matcher = SkimMatcherV2::default();
+        let name = FullyQualifiedName::from(name);
+
+        let (fuzzy_score, _) = match_name(&matcher, &name, query).unwrap();
+        let (boosted_score, _) = score(&matcher, &name, query, false).unwrap();
+
+        assert_eq!(boosted_score - fuzzy_score, boost);
+    }
+
+    #[test]
+    pub fn test_frequently_used_boost() {
+        let matcher = SkimMatcherV2::default();
+        let name = FullyQualifiedName::from("str_replace");
+
+        let (score_used, _) = score(&matcher, &name, "srep", true).unwrap();
+        let (score_unused, _) = score(&matcher, &name, "srep", false).unwrap();
+
+        assert_eq!(score_used - score_unused, FREQUENTLY_USED_BOOST);
+    }
+
+    #[rstest::rstest]
+    // Only `strpos` starts with the query
+    #[case("strp", &["strpos", "str_replace"])]
+    #[case("strlen", &["strlen", "mb_strlen"])]
+    pub fn test_ranking(#[case] query: &str, #[case] expected: &[&str]) {
+        let matcher = SkimMatcherV2::default();
+        let mut names = ["mb_strlen", "str_replace", "strlen", "strpos", "substr"]
+            .into_iter()
+            .filter_map(|name| {
+                let (score, _) = score(&matcher, &FullyQualifiedName::from(name), query, false)?;
+
+                Some((score, name))
+            })
+            .collect::<Vec<_>>();
+        names.sort_by_key(|(score, name)| (std::cmp::Reverse(*score), *name));
+
+        assert_eq!(
+            names.into_iter().map(|(_, name)| name).collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
@@ -0,0 +1,1086 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use tokio::sync::oneshot;
+
+use php_docbook_parser::{
+    chapter::Section,
+    class::Class,
+    constant::Constant,
+    filter::SearchQuery,
+    function::FunctionDefinition,
+    ini::IniDirective,
+    manual::{Entity, Manual},
+    name::FullyQualifiedName,
+    search::Query,
+    signature::SignatureQuery,
+    suggestion::did_you_mean,
+    synonym::Synonyms,
+    variable::{PredefinedVariable, VariableKey},
+};
+
+use super::{ranking, search_modal::SearchModalType};
+use crate::history::{Entry, EntryKind};
+
+/// Queries starting with this search the text of the functions and methods instead of their name
+const FULL_TEXT_PREFIX: char = '/';
+/// Past this, full-text hits hardly match the query anyway
+const FULL_TEXT_LIMIT: usize = 100;
+/// Names suggested when nothing matches a search by name
+const SUGGESTION_LIMIT: usize = 5;
+
+/// How the query is matched, only functions and methods can be searched by their text or
+/// signature
+#[derive(Debug)]
+enum SearchMode {
+    /// Fuzzy matching of the name
+    Name(String),
+    FullText(String),
+    /// `array, callable -> array`
+    Signature(SignatureQuery),
+}
+
+impl SearchMode {
+    /// How to match `text`, the text of the input once its filters are taken out
+    fn of(r#type: SearchModalType, text: &str) -> Self {
+        if !r#type.includes(EntryKind::Function) && !r#type.includes(EntryKind::Method) {
+            return SearchMode::Name(text.to_string());
+        }
+
+        if let Some(text) = text.strip_prefix(FULL_TEXT_PREFIX) {
+            return SearchMode::FullText(text.to_string());
+        }
+
+        SignatureQuery::parse(text)
+            .map_or_else(|| SearchMode::Name(text.to_string()), SearchMode::Signature)
+    }
+}
+
+/// An entry of the search list, borrowed from the [`Manual`]
+#[derive(Debug, Clone, Copy)]
+pub enum SearchItem<'a> {
+    Function(&'a FunctionDefinition),
+    Class(&'a Class),
+    Variable(&'a PredefinedVariable),
+    /// A documented array key of a predefined variable, e.g. `$_SERVER['REQUEST_URI']`
+    VariableKey(&'a PredefinedVariable, &'a VariableKey),
+    /// A section of the language reference, at any depth
+    Chapter(&'a Section),
+    Method(&'a FunctionDefinition),
+    Constant(&'a Constant),
+    IniDirective(&'a IniDirective),
+}
+
+impl<'a> SearchItem<'a> {
+    /// Everything the modal searches: functions, classes, variables followed by their keys,
+    /// sections, methods, constants then ini directives, each sorted by name
+    fn all(manual: &'a Manual, r#type: SearchModalType) -> Vec<Self> {
+        let mut items = Vec::new();
+        if r#type.includes(EntryKind::Function) {
+            items.extend(manual.functions().map(SearchItem::Function));
+        }
+        if r#type.includes(EntryKind::Class) {
+            items.extend(manual.classes().map(SearchItem::Class));
+        }
+        if r#type.includes(EntryKind::Variable) {
+            items.extend(manual.variables().flat_map(|variable| {
+                std::iter::once(SearchItem::Variable(variable)).chain(
+                    variable
+                        .keys
+                        .iter()
+                        .map(move |key| SearchItem::VariableKey(variable, key)),
+                )
+            }));
+        }
+        if r#type.includes(EntryKind::Page) {
+            items.extend(
+                manual
+                    .chapters()
+                    .flat_map(Section::table_of_contents)
+                    .map(|(_, section)| SearchItem::Chapter(section)),
+            );
+        }
+        if r#type.includes(EntryKind::Method) {
+            items.extend(manual.methods().map(SearchItem::Method));
+        }
+        if r#type.includes(EntryKind::Constant) {
+            items.extend(manual.constants().map(SearchItem::Constant));
+        }
+        if r#type.includes(EntryKind::IniDirective) {
+            items.extend(manual.ini_directives().map(SearchItem::IniDirective));
+        }
+
+        items
+    }
+
+    pub fn kind(&self) -> EntryKind {
+        match self {
+            SearchItem::Function(_) => EntryKind::Function,
+            SearchItem::Class(_) => EntryKind::Class,
+            SearchItem::Variable(_) | SearchItem::VariableKey(..) => EntryKind::Variable,
+            SearchItem::Chapter(_) => EntryKind::Page,
+            SearchItem::Method(_) => EntryKind::Method,
+            SearchItem::Constant(_) => EntryKind::Constant,
+            SearchItem::IniDirective(_) => EntryKind::IniDirective,
+        }
+    }
+
+    /// The entity the item documents, the variable for its keys
+    pub fn entity(&self) -> Entity<'a> {
+        match self {
+            SearchItem::Function(function) => Entity::Function(function),
+            SearchItem::Class(class) => Entity::Class(class),
+            SearchItem::Variable(variable) | SearchItem::VariableKey(variable, _) => {
+                Entity::Variable(variable)
+            }
+            SearchItem::Chapter(section) => Entity::Chapter(section),
+            SearchItem::Method(method) => Entity::Method(method),
+            SearchItem::Constant(constant) => Entity::Constant(constant),
+            SearchItem::IniDirective(directive) => Entity::IniDirective(directive),
+        }
+    }
+
+    pub fn name(&self) -> FullyQualifiedName {
+        match self {
+            SearchItem::Function(function) | SearchItem::Method(function) => function.name.clone(),
+            SearchItem::Class(class) => class.name.clone(),
+            SearchItem::Constant(constant) => constant.name.clone(),
+            SearchItem::IniDirective(directive) => FullyQualifiedName {
+                namespace: Vec::new(),
+                name: directive.name.clone(),
+            },
+            SearchItem::Chapter(section) => FullyQualifiedName {
+                namespace: Vec::new(),
+                name: section.title.clone(),
+            },
+            SearchItem::Variable(variable) => FullyQualifiedName {
+                namespace: Vec::new(),
+                name: variable.name.clone(),
+            },
+            SearchItem::VariableKey(variable, key) => FullyQualifiedName {
+                namespace: Vec::new(),
+                name: variable.key_access(key),
+            },
+        }
+    }
+}
+
+/// An item of the list, owned so that it can be matched on another thread and outlive changes of
+/// the manual
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub name: FullyQualifiedName,
+    key: CandidateKey,
+}
+
+/// How to find the item of a [`Candidate`] in the manual
+#[derive(Debug, Clone)]
+enum CandidateKey {
+    Function(FullyQualifiedName),
+    Class(FullyQualifiedName),
+    Variable(String),
+    /// The name of the variable and the position of the key
+    VariableKey(String, usize),
+    /// The key of the section, see [`Section::key`]
+    Chapter(String),
+    Method(FullyQualifiedName),
+    Constant(FullyQualifiedName),
+    IniDirective(String),
+}
+
+impl Candidate {
+    fn new(item: SearchItem) -> Self {
+        let key = match item {
+            SearchItem::Function(function) => CandidateKey::Function(function.name.clone()),
+            SearchItem::Class(class) => CandidateKey::Class(class.name.clone()),
+            SearchItem::Chapter(section) => CandidateKey::Chapter(section.key().to_string()),
+            SearchItem::Method(method) => CandidateKey::Method(method.name.clone()),
+            SearchItem::Constant(constant) => CandidateKey::Constant(constant.name.clone()),
+            SearchItem::IniDirective(directive) => {
+                CandidateKey::IniDirective(directive.name.clone())
+            }
+            SearchItem::Variable(variable) => CandidateKey::Variable(variable.name.clone()),
+            SearchItem::VariableKey(variable, key) => CandidateKey::VariableKey(
+                variable.name.clone(),
+                variable
+                    .keys
+                    .iter()
+                    .position(|other| std::ptr::eq(other, key))
+                    .unwrap_or_default(),
+            ),
+        };
+
+        Self {
+            name: item.name(),
+            key,
+        }
+    }
+
+    pub fn kind(&self) -> EntryKind {
+        match self.key {
+            CandidateKey::Function(_) => EntryKind::Function,
+            CandidateKey::Class(_) => EntryKind::Class,
+            CandidateKey::Variable(_) | CandidateKey::VariableKey(..) => EntryKind::Variable,
+            CandidateKey::Chapter(_) => EntryKind::Page,
+            CandidateKey::Method(_) => EntryKind::Method,
+            CandidateKey::Constant(_) => EntryKind::Constant,
+            CandidateKey::IniDirective(_) => EntryKind::IniDirective,
+        }
+    }
+
+    /// The name the candidate is saved as in the history and bookmarks, the key of sections
+    /// rather than their title
+    pub fn entry_name(&self) -> Cow<'_, FullyQualifiedName> {
+        match &self.key {
+            CandidateKey::Chapter(key) => Cow::Owned(FullyQualifiedName::from(key.as_str())),
+            _ => Cow::Borrowed(&self.name),
+        }
+    }
+
+    pub fn entry(&self) -> Entry {
+        Entry {
+            kind: self.kind(),
+            name: match &self.key {
+                CandidateKey::Chapter(key) => key.clone(),
+                _ => self.name.to_string(),
+            },
+        }
+    }
+
+    /// The item in the manual, `None` if it was removed since
+    pub fn item<'a>(&self, manual: &'a Manual) -> Option<SearchItem<'a>> {
+        let variable = |name: &str| manual.variables().find(|variable| variable.name == name);
+
+        match &self.key {
+            CandidateKey::Function(name) => {
+                manual.function(&name.to_string()).map(SearchItem::Function)
+            }
+            CandidateKey::Class(name) => manual.class(&name.to_string()).map(SearchItem::Class),
+            CandidateKey::Variable(name) => variable(name).map(SearchItem::Variable),
+            CandidateKey::VariableKey(name, key) => {
+                let variable = variable(name)?;
+
+                Some(SearchItem::VariableKey(variable, variable.keys.get(*key)?))
+            }
+            CandidateKey::Chapter(key) => manual.section(key).map(SearchItem::Chapter),
+            CandidateKey::Method(name) => manual.method(&name.to_string()).map(SearchItem::Method),
+            CandidateKey::Constant(name) => {
+                manual.constant(&name.to_string()).map(SearchItem::Constant)
+            }
+            CandidateKey::IniDirective(name) => {
+                manual.ini_directive(name).map(SearchItem::IniDirective)
+            }
+        }
+    }
+}
+
+/// A candidate matching the query
+#[derive(Debug)]
+pub struct SearchMatch {
+    /// Position of the candidate
+    index: usize,
+    /// Indices of the characters of its name which matched the query
+    pub indices: Vec<usize>,
+    /// Whether it was added as a name related to the query rather than matching it
+    pub synonym: bool,
+}
+
+/// Names related to the text of a search by name, added after its matches
+#[derive(Debug)]
+pub struct Expansion {
+    /// The text of the search
+    pub word: String,
+    /// The related names which did not match the text already
+    pub names: Vec<FullyQualifiedName>,
+}
+
+/// What results are computed from, to tell whether they are up to date
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SearchKey {
+    /// The input of the modal, filters included
+    input: String,
+    r#type: SearchModalType,
+    revision: u64,
+}
+
+/// Everything a type of modal searches as of a revision of the manual, built once by the first
+/// search of that revision and shared by the following ones
+#[derive(Debug)]
+struct Corpus {
+    r#type: SearchModalType,
+    revision: u64,
+    candidates: Vec<Candidate>,
+    /// Positions of the function and method candidates, by name
+    functions: HashMap<FullyQualifiedName, usize>,
+    /// Only known for functions, see [`Synonyms`]
+    synonyms: Option<Synonyms>,
+}
+
+impl Corpus {
+    fn new(manual: &Manual, r#type: SearchModalType) -> Self {
+        let candidates = SearchItem::all(manual, r#type)
+            .into_iter()
+            .map(Candidate::new)
+            .collect::<Vec<_>>();
+
+        Self {
+            r#type,
+            revision: manual.revision(),
+            functions: function_positions(&candidates),
+            synonyms: r#type
+                .includes(EntryKind::Function)
+                .then(|| Synonyms::new(manual)),
+            candidates,
+        }
+    }
+
+    /// Positions of the functions related to `text`, see [`Synonyms`]
+    fn related(&self, text: &str) -> Vec<usize> {
+        let Some(synonyms) = &self.synonyms else {
+            return Vec::new();
+        };
+
+        synonyms
+            .expand(text)
+            .iter()
+            .filter_map(|name| self.functions.get(name).copied())
+            .collect()
+    }
+}
+
+/// Matches of a query, the best first
+#[derive(Debug)]
+pub struct SearchResults {
+    key: SearchKey,
+    /// The filters and the matched text, for searches by name
+    name_query: Option<SearchQuery>,
+    /// The terms matched, for the snippets of full-text searches
+    pub text_query: Option<Query>,
+    corpus: Arc<Corpus>,
+    matches: Vec<SearchMatch>,
+    /// Whether nothing matched, the matches being the candidates named closest to the text
+    pub did_you_mean: bool,
+    pub expansion: Option<Expansion>,
+}
+
+impl SearchResults {
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// The matches along with their candidate, the best first
+    pub fn iter(&self) -> impl Iterator<Item = (&Candidate, &SearchMatch)> {
+        self.matches
+            .iter()
+            .map(|search_match| (&self.corpus.candidates[search_match.index], search_match))
+    }
+
+    /// Whether both are results of the same input in the same type of modal, if not of the same
+    /// revision of the manual
+    pub fn is_same_search(&self, other: &SearchResults) -> bool {
+        self.key.input == other.key.input && self.key.r#type == other.key.r#type
+    }
+
+    pub fn get(&self, position: usize) -> Option<&Candidate> {
+        self.matches
+            .get(position)
+            .map(|search_match| &self.corpus.candidates[search_match.index])
+    }
+
+    /// Positions of the candidates which can match a search by name for `text`, if it extends the
+    /// text of these results with the same filters. A name matching a text also matches every
+    /// prefix of it, so only the previous matches can match an extension of their text
+    fn extended_scope(
+        &self,
+        key: &SearchKey,
+        query: &SearchQuery,
+        text: &str,
+    ) -> Option<Vec<usize>> {
+        let previous_query = self.name_query.as_ref()?;
+        let extends = self.key.revision == key.revision
+            && self.key.r#type == key.r#type
+            && !self.did_you_mean
+            && previous_query.filters == query.filters
+            && text.starts_with(&previous_query.text);
+
+        extends.then(|| {
+            self.matches
+                .iter()
+                .map(|search_match| search_match.index)
+                .collect()
+        })
+    }
+
+    /// Matches `query` against the corpus, only among the candidates in `scope` if known
+    ///
+    /// Runs on a blocking task, as filters and signatures look every item up in the manual
+    fn compute(
+        mut self,
+        manual: &Manual,
+        query: SearchQuery,
+        mode: SearchMode,
+        scope: Option<Vec<usize>>,
+        frequently_used: &HashSet<String>,
+    ) -> Self {
+        let r#type = self.key.r#type;
+
+        let text = match mode {
+            SearchMode::FullText(text) => {
+                let text_query = Query::new(&text);
+                self.matches =
+                    full_text_matches(manual, &self.corpus.functions, &query, &text_query);
+                self.text_query = Some(text_query);
+
+                return self;
+            }
+            SearchMode::Signature(signature) => {
+                let items = SearchItem::all(manual, r#type);
+                self.matches = signature_matches(manual, &items, &query, &signature);
+
+                return self;
+            }
+            SearchMode::Name(text) => text,
+        };
+
+        // The candidates are the items, in the same order, as the manual did not change since
+        let items = (!query.filters.is_empty()).then(|| SearchItem::all(manual, r#type));
+        let filtered = |index: &usize| {
+            items
+                .as_ref()
+                .is_none_or(|items| query.matches(manual, items[*index].entity()))
+        };
+        let scope =
+            scope.unwrap_or_else(|| (0..self.corpus.candidates.len()).filter(filtered).collect());
+        let related = self
+            .corpus
+            .related(&text)
+            .into_iter()
+            .filter(filtered)
+            .collect();
+
+        self.matches = rank(
+            &SkimMatcherV2::default(),
+            &self.corpus.candidates,
+            scope,
+            &text,
+            frequently_used,
+        );
+        self.name_query = Some(query);
+
+        self.with_synonyms(&text, related).suggest_if_empty(&text)
+    }
+
+    /// Suggests the candidates named closest to `text` if no name matched it, most likely because
+    /// it is misspelled. Filters are left out, as they may be what left nothing
+    fn suggest_if_empty(mut self, text: &str) -> Self {
+        if !self.matches.is_empty() || text.trim().is_empty() {
+            return self;
+        }
+
+        let candidates = self
+            .corpus
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| (candidate.name.to_string(), index));
+        self.matches = did_you_mean(text, candidates, SUGGESTION_LIMIT)
+            .into_iter()
+            .map(|index| SearchMatch {
+                index,
+                indices: Vec::new(),
+                synonym: false,
+            })
+            .collect();
+        self.did_you_mean = !self.matches.is_empty();
+
+        self
+    }
+
+    /// Adds the candidates at `related` positions after the matches, unless they matched already
+    fn with_synonyms(mut self, text: &str, related: Vec<usize>) -> Self {
+        let matched = self
+            .matches
+            .iter()
+            .map(|search_match| search_match.index)
+            .collect::<HashSet<_>>();
+        let related = related
+            .into_iter()
+            .filter(|index| !matched.contains(index))
+            .collect::<Vec<_>>();
+        if related.is_empty() {
+            return self;
+        }
+
+        self.expansion = Some(Expansion {
+            word: text.to_string(),
+            names: related
+                .iter()
+                .map(|index| self.corpus.candidates[*index].name.clone())
+                .collect(),
+        });
+        self.matches
+            .extend(related.into_iter().map(|index| SearchMatch {
+                index,
+                indices: Vec::new(),
+                synonym: true,
+            }));
+
+        self
+    }
+}
+
+/// Matches the input of a search modal as it changes, off the event loop
+///
+/// Every search runs on a blocking task, which builds the corpus of a new revision of the manual
+/// first. Extending a search by name only rescores the previous matches. The widget renders the
+/// latest results until newer ones are ready
+#[derive(Debug, Default)]
+pub struct SearchEngine {
+    /// The corpus of the latest results, reused until the manual or the type of modal changes
+    corpus: Option<Arc<Corpus>>,
+    results: Option<SearchResults>,
+    /// What is being matched in the background, and where its results will arrive
+    pending: Option<(SearchKey, oneshot::Receiver<SearchResults>)>,
+    /// See [`ranking::frequently_used`]
+    frequently_used: Arc<HashSet<String>>,
+}
+
+impl SearchEngine {
+    pub fn new(frequently_used: HashSet<String>) -> Self {
+        Self {
+            frequently_used: Arc::new(frequently_used),
+            ..Default::default()
+        }
+    }
+
+    /// The latest results, which may be for an older input while newer ones are computed
+    pub fn results(&self) -> Option<&SearchResults> {
+        self.results.as_ref()
+    }
+
+    /// Matches `input` against the manual in the background, unless it was already
+    ///
+    /// The task holds the manual until it is done, see [`crate::tui::SharedState::apply`]
+    pub fn search(&mut self, manual: &Arc<Manual>, r#type: SearchModalType, input: &str) {
+        let key = SearchKey {
+            input: input.to_string(),
+            r#type,
+            revision: manual.revision(),
+        };
+        if self.is_current(&key) {
+            return;
+        }
+
+        let query = SearchQuery::parse(input);
+        let mode = SearchMode::of(r#type, &query.text);
+        let scope = match &mode {
+            SearchMode::Name(text) => self
+                .results
+                .as_ref()
+                .and_then(|previous| previous.extended_scope(&key, &query, text)),
+            SearchMode::FullText(_) | SearchMode::Signature(_) => None,
+        };
+        let corpus = self
+            .corpus
+            .clone()
+            .filter(|corpus| corpus.r#type == r#type && corpus.revision == key.revision);
+
+        let (sender, receiver) = oneshot::channel();
+        let manual = manual.clone();
+        let frequently_used = self.frequently_used.clone();
+        let results_key = key.clone();
+        tokio::task::spawn_blocking(move || {
+            let results = SearchResults {
+                key: results_key,
+                name_query: None,
+                text_query: None,
+                corpus: corpus.unwrap_or_else(|| Arc::new(Corpus::new(&manual, r#type))),
+                matches: Vec::new(),
+                did_you_mean: false,
+                expansion: None,
+            }
+            .compute(&manual, query, mode, scope, &frequently_used);
+            // Parse events are applied once no search holds the manual, which must not wait for
+            // the results to be received
+            drop(manual);
+
+            // The input changed since, or the modal was closed
+            let _ = sender.send(results);
+        });
+        // Dropping the receiver of the previous search, whose results would be outdated
+        self.pending = Some((key, receiver));
+    }
+
+    /// Whether the results, or the search running in the background, are for `key` already
+    fn is_current(&self, key: &SearchKey) -> bool {
+        match &self.pending {
+            Some((pending, _)) => pending == key,
+            None => self
+                .results
+                .as_ref()
+                .is_some_and(|results| results.key == *key),
+        }
+    }
+
+    /// Waits for the results of the search running in the background, forever if there is none
+    pub async fn background_results(&mut self) -> SearchResults {
+        if let Some((_, receiver)) = &mut self.pending {
+            let results = receiver.await;
+            self.pending = None;
+
+            if let Ok(results) = results {
+                return results;
+            }
+        }
+
+        std::future::pending().await
+    }
+
+    /// Takes the results of a search which ran in the background, keeping its corpus for the
+    /// next searches
+    pub fn receive(&mut self, results: SearchResults) {
+        self.corpus = Some(results.corpus.clone());
+        self.results = Some(results);
+    }
+}
+
+/// Matches of the name of the candidates at the positions in `scope`, the best first
+fn rank(
+    matcher: &SkimMatcherV2,
+    candidates: &[Candidate],
+    scope: Vec<usize>,
+    text: &str,
+    frequently_used: &HashSet<String>,
+) -> Vec<SearchMatch> {
+    let mut matches = scope
+        .into_iter()
+        .filter_map(|index| {
+            let candidate = &candidates[index];
+            let is_frequently_used =
+                matches!(candidate.kind(), EntryKind::Function | EntryKind::Method)
+                    && frequently_used.contains(&candidate.name.to_string().to_lowercase());
+            let (score, indices) =
+                ranking::score(matcher, &candidate.name, text, is_frequently_used)?;
+
+            Some((score, index, indices))
+        })
+        .collect::<Vec<_>>();
+    // Candidates are sorted by kind then name, which breaks ties
+    matches.sort_unstable_by_key(|(score, index, _)| (std::cmp::Reverse(*score), *index));
+
+    matches
+        .into_iter()
+        .map(|(_, index, indices)| SearchMatch {
+            index,
+            indices,
+            synonym: false,
+        })
+        .collect()
+}
+
+/// Functions and methods whose text matches the query, the most relevant first
+fn full_text_matches(
+    manual: &Manual,
+    functions: &HashMap<FullyQualifiedName, usize>,
+    filters: &SearchQuery,
+    query: &Query,
+) -> Vec<SearchMatch> {
+    manual
+        .search_text(query)
+        .filter(|hit| filters.matches(manual, hit.entity))
+        .filter_map(|hit| {
+            let (Entity::Function(function) | Entity::Method(function)) = hit.entity else {
+                return None;
+            };
+            let index = *functions.get(&function.name)?;
+
+            Some(SearchMatch {
+                index,
+                indices: Vec::new(),
+                synonym: false,
+            })
+        })
+        .take(FULL_TEXT_LIMIT)
+        .collect()
+}
+
+/// Functions and methods matching the signature, the closest first
+fn signature_matches(
+    manual: &Manual,
+    items: &[SearchItem],
+    filters: &SearchQuery,
+    signature: &SignatureQuery,
+) -> Vec<SearchMatch> {
+    let mut matches = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| filters.matches(manual, item.entity()))
+        .filter_map(|(index, item)| match item {
+            SearchItem::Function(function) | SearchItem::Method(function) => {
+                Some((signature.distance(function)?, index))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    // Functions and methods are sorted by name already
+    matches.sort_by_key(|(distance, _)| *distance);
+
+    matches
+        .into_iter()
+        .map(|(_, index)| SearchMatch {
+            index,
+            indices: Vec::new(),
+            synonym: false,
+        })
+        .collect()
+}
+
+/// Positions of the function and method candidates, by name
+fn function_positions(candidates: &[Candidate]) -> HashMap<FullyQualifiedName, usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| match &candidate.key {
+            CandidateKey::Function(name) | CandidateKey::Method(name) => {
+                Some((name.clone(), index))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, sync::Arc};
+
+    use tokio::sync::oneshot;
+
+    use php_docbook_parser::{
+        class::Class,
+        constant::Constant,
+        filter::SearchQuery,
+        function::FunctionDefinition,
+        ini::IniDirective,
+        manual::{Manual, Page},
+        name::FullyQualifiedName,
+    };
+
+    use super::{SearchEngine, SearchKey, SearchModalType};
+    use crate::history::EntryKind;
+
+    fn function(name: &str) -> Page {
+        FunctionDefinition::stub(name).page()
+    }
+
+    fn manual() -> Arc<Manual> {
+        let mut manual = Manual::default();
+        for (path, page) in [
+            ("reference/strings/functions/strlen.xml", function("strlen")),
+            ("reference/strings/functions/strpos.xml", function("strpos")),
+            (
+                "reference/strings/functions/str-replace.xml",
+                function("str_replace"),
+            ),
+            (
+                "reference/mbstring/functions/mb-strlen.xml",
+                function("mb_strlen"),
+            ),
+            (
+                "reference/strings/class.stringable.xml",
+                Page::Class(Some(Class {
+                    name: FullyQualifiedName::from("Stringable"),
+                    kind: Default::default(),
+                    modifiers: Vec::new(),
+                    attributes: Vec::new(),
+                    extends: None,
+                    implements: Vec::new(),
+                    description: Vec::new(),
+                })),
+            ),
+        ] {
+            manual.insert(PathBuf::from(path), page);
+        }
+
+        Arc::new(manual)
+    }
+
+    /// Inserts a page, once the searches are done with the manual
+    fn insert(manual: &mut Arc<Manual>, path: &str, page: Page) {
+        Arc::get_mut(manual)
+            .expect("no search holds the manual")
+            .insert(PathBuf::from(path), page);
+    }
+
+    fn key(manual: &Manual, r#type: SearchModalType, input: &str) -> SearchKey {
+        SearchKey {
+            input: input.to_string(),
+            r#type,
+            revision: manual.revision(),
+        }
+    }
+
+    /// Searches, then waits for the results unless they were current already
+    async fn search(
+        engine: &mut SearchEngine,
+        manual: &Arc<Manual>,
+        r#type: SearchModalType,
+        input: &str,
+    ) {
+        engine.search(manual, r#type, input);
+        if engine.pending.is_some() {
+            let results = engine.background_results().await;
+            engine.receive(results);
+        }
+    }
+
+    /// The names matched by a new engine, which searches every candidate
+    async fn fresh_names(
+        manual: &Arc<Manual>,
+        r#type: SearchModalType,
+        input: &str,
+    ) -> Vec<String> {
+        let mut engine = SearchEngine::default();
+        search(&mut engine, manual, r#type, input).await;
+
+        names(&engine)
+    }
+
+    fn names(engine: &SearchEngine) -> Vec<String> {
+        engine
+            .results()
+            .map(|results| {
+                results
+                    .iter()
+                    .map(|(candidate, _)| candidate.name.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    pub async fn test_extending_the_query() {
+        let manual = manual();
+        let mut engine = SearchEngine::default();
+
+        search(&mut engine, &manual, SearchModalType::All, "str").await;
+        let results = engine.results().unwrap();
+        let scope = results.extended_scope(
+            &key(&manual, SearchModalType::All, "strle"),
+            &SearchQuery::parse("strle"),
+            "strle",
+        );
+        assert_eq!(scope.map(|scope| scope.len()), Some(results.len()));
+        // Shortening the text may match names the previous search did not
+        assert!(
+            results
+                .extended_scope(
+                    &key(&manual, SearchModalType::All, "st"),
+                    &SearchQuery::parse("st"),
+                    "st",
+                )
+                .is_none()
+        );
+
+        search(&mut engine, &manual, SearchModalType::All, "strle").await;
+        assert_eq!(names(&engine)[0], "strlen");
+        assert!(!names(&engine).contains(&"strpos".to_string()));
+        assert_eq!(
+            names(&engine),
+            fresh_names(&manual, SearchModalType::All, "strle").await
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_changing_filters() {
+        let manual = manual();
+        let mut engine = SearchEngine::default();
+
+        search(
+            &mut engine,
+            &manual,
+            SearchModalType::All,
+            "ext:strings strl",
+        )
+        .await;
+        assert!(!names(&engine).contains(&"mb_strlen".to_string()));
+        assert!(
+            engine
+                .results()
+                .unwrap()
+                .extended_scope(
+                    &key(&manual, SearchModalType::All, "strle"),
+                    &SearchQuery::parse("strle"),
+                    "strle",
+                )
+                .is_none()
+        );
+
+        search(&mut engine, &manual, SearchModalType::All, "strle").await;
+        assert!(names(&engine).contains(&"mb_strlen".to_string()));
+        assert_eq!(
+            names(&engine),
+            fresh_names(&manual, SearchModalType::All, "strle").await
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_revision_bump_mid_search() {
+        let mut manual = manual();
+        let mut engine = SearchEngine::default();
+
+        search(&mut engine, &manual, SearchModalType::All, "str").await;
+        let (sender, receiver) = oneshot::channel();
+        let pending = key(&manual, SearchModalType::All, "strc");
+        engine.pending = Some((pending.clone(), receiver));
+        assert!(engine.is_current(&pending));
+
+        insert(
+            &mut manual,
+            "reference/strings/functions/str-contains.xml",
+            function("str_contains"),
+        );
+        assert!(!engine.is_current(&key(&manual, SearchModalType::All, "strc")));
+        // The matches of `str` predate the function
+        assert!(
+            engine
+                .results()
+                .unwrap()
+                .extended_scope(
+                    &key(&manual, SearchModalType::All, "strc"),
+                    &SearchQuery::parse("strc"),
+                    "strc",
+                )
+                .is_none()
+        );
+
+        search(&mut engine, &manual, SearchModalType::All, "strc").await;
+        assert!(engine.pending.is_none());
+        // The outdated results are dropped once ready
+        assert!(sender.is_closed());
+        assert_eq!(names(&engine)[0], "str_contains");
+        assert_eq!(
+            names(&engine),
+            fresh_names(&manual, SearchModalType::All, "strc").await
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_reusing_the_corpus() {
+        let mut manual = manual();
+        let mut engine = SearchEngine::default();
+
+        search(&mut engine, &manual, SearchModalType::All, "str").await;
+        let corpus = engine.corpus.clone().unwrap();
+        // Full-text and signature searches share it too
+        for input in ["st", "/length", "string -> int", "ext:strings len"] {
+            search(&mut engine, &manual, SearchModalType::All, input).await;
+            assert!(Arc::ptr_eq(engine.corpus.as_ref().unwrap(), &corpus));
+        }
+
+        insert(
+            &mut manual,
+            "reference/strings/functions/str-contains.xml",
+            function("str_contains"),
+        );
+        search(&mut engine, &manual, SearchModalType::All, "str").await;
+        assert!(!Arc::ptr_eq(engine.corpus.as_ref().unwrap(), &corpus));
+        assert_eq!(engine.corpus.as_ref().unwrap().revision, manual.revision());
+    }
+
+    #[tokio::test]
+    pub async fn test_switching_kind() {
+        let manual = manual();
+        let mut engine = SearchEngine::default();
+
+        search(&mut engine, &manual, SearchModalType::All, "stri").await;
+        assert!(names(&engine).contains(&"Stringable".to_string()));
+        let functions = SearchModalType::Only(EntryKind::Function);
+        assert!(
+            engine
+                .results()
+                .unwrap()
+                .extended_scope(
+                    &key(&manual, functions, "strin"),
+                    &SearchQuery::parse("strin"),
+                    "strin",
+                )
+                .is_none()
+        );
+
+        search(&mut engine, &manual, functions, "strin").await;
+        assert!(!names(&engine).contains(&"Stringable".to_string()));
+        assert!(
+            engine
+                .corpus
+                .as_ref()
+                .is_some_and(|corpus| corpus.r#type == functions)
+        );
+
+        search(
+            &mut engine,
+            &manual,
+            SearchModalType::Only(EntryKind::Class),
+            "strin",
+        )
+        .await;
+        assert_eq!(names(&engine), ["Stringable"]);
+    }
+
+    #[tokio::test]
+    pub async fn test_searching_every_kind() {
+        let mut manual = manual();
+        insert(
+            &mut manual,
+            "reference/strings/stringable/tostring.xml",
+            Page::Method(Some((
+                FunctionDefinition::stub("Stringable::__toString"),
+                Default::default(),
+            ))),
+        );
+        insert(
+            &mut manual,
+            "reference/strings/constants.xml",
+            Page::Constants(vec![Constant {
+                name: FullyQualifiedName::from("STR_PAD_LEFT"),
+                r#type: None,
+                description: Vec::new(),
+            }]),
+        );
+        insert(
+            &mut manual,
+            "reference/info/ini.xml",
+            Page::IniDirectives(vec![IniDirective {
+                name: "default_charset".to_string(),
+                r#type: None,
+                default_value: None,
+                changeable: None,
+                description: Vec::new(),
+            }]),
+        );
+
+        let all = fresh_names(&manual, SearchModalType::All, "str").await;
+        assert!(all.contains(&"Stringable::__toString".to_string()));
+        assert!(all.contains(&"STR_PAD_LEFT".to_string()));
+        for (kind, input, expected) in [
+            (EntryKind::Method, "tostr", "Stringable::__toString"),
+            (EntryKind::Constant, "pad", "STR_PAD_LEFT"),
+            (EntryKind::IniDirective, "charset", "default_charset"),
+        ] {
+            let mut engine = SearchEngine::default();
+            search(&mut engine, &manual, SearchModalType::Only(kind), input).await;
+            assert_eq!(names(&engine), [expected]);
+
+            let candidate = engine.results().unwrap().get(0).unwrap();
+            assert_eq!(candidate.kind(), kind);
+            assert!(
+                candidate
+                    .item(&manual)
+                    .is_some_and(|item| item.kind() == kind)
+            );
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use crossterm::event::{KeyCode, KeyEvent};
-use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashSet;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Margin, Rect},
@@ -12,10 +13,36 @@ use ratatui::{
 };
 use tui_input::Input;
 
-use crate::tui::{EventHandler, EventHandlerResult, SharedState};
-use php_docbook_parser::function::Function;
+use crate::{
+    history::{Entry, EntryKind, History},
+    tui::{
+        EventHandler, EventHandlerResult, SharedState, event::Message,
+        screen::manual_screen::render_section,
+    },
+};
+use php_docbook_parser::{
+    filter::{Filter, SearchQuery},
+    function::FunctionBody,
+    name::FullyQualifiedName,
+    search::Snippet,
+    text::TextNode,
+};
 
-use super::Modal;
+use super::{
+    Modal, ranking,
+    search_engine::{Candidate, Expansion, SearchEngine, SearchItem, SearchResults},
+};
+
+/// Results kept in view above and below the selection
+const SCROLL_PADDING: usize = 2;
+/// Keys and syntax of the search along with what they do, listed below the results
+const HINTS: &[(&str, &str)] = &[
+    ("[Tab]", "switch kind"),
+    ("/text", "search the text of functions"),
+    ("array, callable -> array", "search by signature"),
+    ("ext: returns: param: since: -deprecated", "filter"),
+    ("[Ctrl+B]", "star"),
+];
 
 #[derive(Debug)]
 pub struct SearchModal {
@@ -23,32 +50,261 @@ pub struct SearchModal {
     query: Input,
     list_state: ListState,
     vertical_scroll_state: ScrollbarState,
+    engine: SearchEngine,
+    /// Previous searches, the latest first
+    history: Vec<String>,
+    /// Which of the previous searches is in the input, while cycling through them
+    history_position: Option<usize>,
 }
 
 impl SearchModal {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new(r#type: SearchModalType) -> Modal {
+    pub fn new(r#type: SearchModalType, history: &History, query: String) -> Modal {
         Modal::SearchModal(Self {
             r#type,
-            query: Input::default(),
+            query: Input::new(query),
             list_state: ListState::default(),
             vertical_scroll_state: ScrollbarState::default(),
+            engine: SearchEngine::new(ranking::frequently_used(history)),
+            history: history.searches().map(str::to_string).collect(),
+            history_position: None,
         })
     }
 
-    pub fn r#type(&self) -> &SearchModalType {
-        &self.r#type
+    /// Puts the previous search at `position` in the input, clearing it past the latest one
+    fn recall(&mut self, position: Option<usize>) {
+        let query = position
+            .and_then(|position| self.history.get(position))
+            .cloned()
+            .unwrap_or_default();
+
+        self.query = Input::new(query);
+        self.history_position = position;
+        self.list_state.select(None);
+    }
+
+    /// Searches the next or previous kinds, the selection being meaningless for them
+    fn cycle(&mut self, r#type: SearchModalType) {
+        self.r#type = r#type;
+        self.list_state.select(None);
+        self.vertical_scroll_state = ScrollbarState::default();
+    }
+
+    fn selected_entry(&self) -> Option<Entry> {
+        self.list_state
+            .selected()
+            .and_then(|selected| self.engine.results()?.get(selected))
+            .map(Candidate::entry)
+    }
+
+    /// Records the search and the selected entry in the history
+    fn submit(&self) -> Message {
+        Message::RecordSearch {
+            query: self.query.value().to_string(),
+            opened: self.selected_entry(),
+        }
+    }
+
+    /// Matches the input, once it or the manual changed since the last search
+    pub fn search(&mut self, state: &SharedState) {
+        self.engine
+            .search(&state.manual, self.r#type, self.query.value());
+    }
+
+    pub fn engine_mut(&mut self) -> &mut SearchEngine {
+        &mut self.engine
+    }
+
+    /// Takes the results of a search which ran in the background, unselecting the previous
+    /// results unless they were only updated along with the manual
+    pub fn receive(&mut self, results: SearchResults) {
+        if !self
+            .engine
+            .results()
+            .is_some_and(|previous| previous.is_same_search(&results))
+        {
+            self.list_state = ListState::default();
+            self.vertical_scroll_state = ScrollbarState::default();
+        }
+
+        self.engine.receive(results);
     }
 }
 
-#[derive(Debug)]
+/// What the modal searches, cycled through with Tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchModalType {
-    Function,
+    All,
+    Only(EntryKind),
+}
+
+impl SearchModalType {
+    /// The order of the tabs
+    const CYCLE: [Self; 8] = [
+        Self::All,
+        Self::Only(EntryKind::Function),
+        Self::Only(EntryKind::Method),
+        Self::Only(EntryKind::Class),
+        Self::Only(EntryKind::Constant),
+        Self::Only(EntryKind::IniDirective),
+        Self::Only(EntryKind::Variable),
+        Self::Only(EntryKind::Page),
+    ];
+
+    fn position(self) -> usize {
+        Self::CYCLE
+            .iter()
+            .position(|other| *other == self)
+            .unwrap_or_default()
+    }
+
+    fn next(self) -> Self {
+        Self::CYCLE[(self.position() + 1) % Self::CYCLE.len()]
+    }
+
+    fn previous(self) -> Self {
+        Self::CYCLE[(self.position() + Self::CYCLE.len() - 1) % Self::CYCLE.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Only(EntryKind::Function) => "Functions",
+            Self::Only(EntryKind::Class) => "Classes",
+            Self::Only(EntryKind::Variable) => "Variables",
+            Self::Only(EntryKind::Page) => "Manual pages",
+            Self::Only(EntryKind::Method) => "Methods",
+            Self::Only(EntryKind::Constant) => "Constants",
+            Self::Only(EntryKind::IniDirective) => "Ini directives",
+        }
+    }
+
+    pub fn includes(self, kind: EntryKind) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only(only) => only == kind,
+        }
+    }
+}
+
+/// The icon set before the items of a kind, in its color
+fn kind_icon(kind: EntryKind) -> Span<'static> {
+    let (icon, color) = match kind {
+        EntryKind::Function => ("ƒ ", Color::LightBlue),
+        EntryKind::Class => ("◆ ", Color::LightMagenta),
+        EntryKind::Variable => ("$ ", Color::LightGreen),
+        EntryKind::Page => ("§ ", Color::Yellow),
+        EntryKind::Method => ("∷ ", Color::LightCyan),
+        EntryKind::Constant => ("π ", Color::Cyan),
+        EntryKind::IniDirective => ("≡ ", Color::Gray),
+    };
+
+    Span::styled(icon, Style::default().fg(color))
+}
+
+impl SearchItem<'_> {
+    /// `body` is the lazily parsed body of the function, for function and method items
+    fn preview(&self, body: Option<&FunctionBody>) -> String {
+        match self {
+            SearchItem::Function(function) | SearchItem::Method(function) => format!(
+                "{function}\n\n{}",
+                body.map(|body| join_text_nodes(&body.description))
+                    .unwrap_or_default()
+            ),
+            SearchItem::Class(class) => {
+                format!("{class}\n\n{}", join_text_nodes(&class.description))
+            }
+            SearchItem::Chapter(section) => render_section(section, 1),
+            SearchItem::Variable(variable) => {
+                let mut preview = format!(
+                    "{}\n\n{}\n\n{}",
+                    variable.name,
+                    variable.short_description,
+                    join_text_nodes(&variable.description)
+                );
+
+                for key in &variable.keys {
+                    preview.push_str(&format!(
+                        "\n\n{}\n{}",
+                        variable.key_access(key),
+                        join_text_nodes(&key.description)
+                    ));
+                }
+
+                preview
+            }
+            SearchItem::VariableKey(variable, key) => format!(
+                "{}\n\n{}\n\n{}: {}",
+                variable.key_access(key),
+                join_text_nodes(&key.description),
+                variable.name,
+                variable.short_description
+            ),
+            SearchItem::Constant(constant) => {
+                format!("{constant}\n\n{}", join_text_nodes(&constant.description))
+            }
+            SearchItem::IniDirective(directive) => {
+                let mut preview = directive.to_string();
+                if let Some(changeable) = &directive.changeable {
+                    preview.push_str(&format!("\nChangeable: {changeable}"));
+                }
+                preview.push_str(&format!("\n\n{}", join_text_nodes(&directive.description)));
+
+                preview
+            }
+        }
+    }
+}
+
+fn join_text_nodes(nodes: &[TextNode]) -> String {
+    nodes
+        .iter()
+        .map(|node| node.to_string())
+        .map(|str| str.trim().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 impl EventHandler for SearchModal {
     async fn on_key_event(&mut self, key: &KeyEvent) -> EventHandlerResult {
+        // Previous searches are recalled from an empty input, until something else is typed
+        let recalling = self.query.value().is_empty() || self.history_position.is_some();
         match key.code {
+            KeyCode::Up if recalling && !self.history.is_empty() => {
+                let position = self
+                    .history_position
+                    .map_or(0, |position| (position + 1).min(self.history.len() - 1));
+                self.recall(Some(position));
+
+                return EventHandlerResult::Handled;
+            }
+            KeyCode::Down if recalling && self.history_position.is_some() => {
+                self.recall(
+                    self.history_position
+                        .and_then(|position| position.checked_sub(1)),
+                );
+
+                return EventHandlerResult::Handled;
+            }
+            KeyCode::Enter => return EventHandlerResult::HandledWithMessage(self.submit()),
+            KeyCode::Tab => {
+                self.cycle(self.r#type.next());
+
+                return EventHandlerResult::Handled;
+            }
+            KeyCode::BackTab => {
+                self.cycle(self.r#type.previous());
+
+                return EventHandlerResult::Handled;
+            }
+            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return match self.selected_entry() {
+                    Some(entry) => {
+                        EventHandlerResult::HandledWithMessage(Message::ToggleBookmark(entry))
+                    }
+                    None => EventHandlerResult::Handled,
+                };
+            }
             KeyCode::Down => {
                 self.vertical_scroll_state.next();
                 self.list_state.select_next();
@@ -76,7 +332,11 @@ impl EventHandler for SearchModal {
 
         use tui_input::backend::crossterm::EventHandler as _;
         match self.query.handle_event(&crossterm::event::Event::Key(*key)) {
-            Some(_) => EventHandlerResult::Handled,
+            Some(_) => {
+                self.history_position = None;
+
+                EventHandlerResult::Handled
+            }
             None => EventHandlerResult::Pass,
         }
     }
@@ -86,9 +346,16 @@ impl StatefulWidget for &mut SearchModal {
     type State = SharedState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let [input_area, preview_and_list_area] =
-            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).split(area)[..]
-        else {
+        let query = SearchQuery::parse(self.query.value());
+
+        let hints = hint_lines(HINTS, area.width.into());
+        let [input_area, tabs_area, preview_and_list_area, hints_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(hints.len().try_into().unwrap_or(u16::MAX)),
+        ])
+        .split(area)[..] else {
             unreachable!()
         };
 
@@ -102,92 +369,134 @@ impl StatefulWidget for &mut SearchModal {
         let input_widget = Paragraph::new(self.query.value()).block(Block::bordered());
         input_widget.render(input_area, buf);
 
-        let items = state
-            .parsed_files_snapshot
+        let mut tabs = SearchModalType::CYCLE
             .iter()
-            .filter_map(|function| match function {
-                Function::Definition(function_definition) => Some(function_definition),
-                Function::Alias(_) => None,
-            })
-            .filter_map(|function| {
-                let (_, indices) = state
-                    .fuzzy_matcher
-                    .fuzzy_indices(function.name.as_str(), self.query.value())?;
-
-                Some((
-                    function,
-                    function
-                        .name
-                        .chars()
-                        .enumerate()
-                        .map(|(i, char)| (char, indices.contains(&i)))
-                        .collect::<Vec<_>>(),
-                ))
+            .flat_map(|r#type| {
+                let style = if *r#type == self.r#type {
+                    Style::default().fg(Color::Black).bg(Color::White).bold()
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+
+                [
+                    Span::styled(format!(" {} ", r#type.label()), style),
+                    Span::raw(" "),
+                ]
             })
             .collect::<Vec<_>>();
+        tabs.push(Span::raw(" "));
+        tabs.extend(query.filters.iter().flat_map(filter_chip));
+        Line::from(tabs).render(tabs_area, buf);
 
-        let selected_function = items
-            .iter()
-            .enumerate()
-            .find(|(i, _)| Some(*i) == self.list_state.selected())
-            .map(|(_, function)| function.0)
-            .cloned();
+        // Results of an older input are shown until the search of the current one completes
+        let results = self.engine.results();
+        let items_count = results.map_or(0, |results| results.len());
+        // The results may have shrunk since the selection was made, e.g. `End` selects past them
+        self.list_state.select(
+            self.list_state
+                .selected()
+                .and_then(|selected| Some(selected.min(items_count.checked_sub(1)?))),
+        );
+        let selected = self.list_state.selected();
+        let selected_item = selected
+            .and_then(|selected| results?.get(selected))
+            .and_then(|candidate| candidate.item(&state.manual));
 
-        let items_count = items.len();
+        // Compared by name rather than formatting the name of every item
+        let starred = state
+            .bookmarks
+            .iter()
+            .filter(|entry| self.r#type.includes(entry.kind))
+            .map(|entry| (entry.kind, FullyQualifiedName::from(entry.name.as_str())))
+            .collect::<Vec<_>>();
 
-        let items = items
+        let manual = &state.manual;
+        let function_bodies = &mut state.function_bodies;
+        let text_query = results.and_then(|results| results.text_query.as_ref());
+        // Only the rows which fit are built, out of possibly thousands of results
+        let rows = usize::from(list_area.height);
+        let offset = visible_offset(self.list_state.offset(), selected, rows, items_count);
+        *self.list_state.offset_mut() = offset;
+        let items = results
             .into_iter()
+            .flat_map(|results| results.iter())
             .enumerate()
-            .map(|(i, (_, function_name))| {
+            .skip(offset)
+            .take(rows)
+            .map(|(i, (candidate, search_match))| {
                 let mut chars = Vec::<Span>::new();
-                // .find(|(i, _)| Some(*i) == self.list_state.selected())
-                let is_selected = self
-                    .list_state
-                    .selected()
-                    .map(|selected| selected == i)
-                    .unwrap_or_default();
+                let is_selected = selected == Some(i);
                 if is_selected {
                     chars.push(Span::styled("> ", Style::default().fg(Color::LightRed)));
                 }
+                chars.push(kind_icon(candidate.kind()));
 
-                for (char, matching) in function_name {
+                for (run, matching, is_namespace) in
+                    name_runs(&candidate.name, &search_match.indices)
+                {
                     chars.push(Span::styled(
-                        char.to_string(),
-                        matching
-                            .then_some(Style::default().fg(Color::LightRed))
-                            .unwrap_or_default(),
+                        run,
+                        match (matching, is_namespace) {
+                            (true, _) => Style::default().fg(Color::LightRed),
+                            (false, true) => Style::default().fg(Color::DarkGray),
+                            (false, false) => Style::default(),
+                        },
                     ));
                 }
+                if starred.iter().any(|(kind, name)| {
+                    *kind == candidate.kind() && *name == *candidate.entry_name()
+                }) {
+                    chars.push(Span::styled(" ★", Style::default().fg(Color::Yellow)));
+                }
+                if search_match.synonym {
+                    chars.push(Span::styled(" ≈", Style::default().fg(Color::DarkGray)));
+                }
+
+                let mut lines = vec![Line::from(chars)];
+                // The manual only keeps the terms of the pages, their text is parsed once shown
+                let snippet = text_query.and_then(|query| {
+                    let path = manual.source(candidate.item(manual)?.entity())?;
+
+                    Snippet::new(function_bodies.text(path)?, query)
+                });
+                lines.extend(snippet.as_ref().map(snippet_line));
 
                 if is_selected {
-                    ListItem::new(Line::from(chars))
-                        .style(Style::new().italic().bg(Color::DarkGray))
+                    ListItem::new(lines).style(Style::new().italic().bg(Color::DarkGray))
                 } else {
-                    ListItem::new(Line::from(chars))
+                    ListItem::new(lines)
                 }
             });
 
-        let list = List::new(items).scroll_padding(2);
-        StatefulWidget::render(list, list_area, buf, &mut self.list_state);
+        let mut list = List::new(items).scroll_padding(SCROLL_PADDING);
+        if results.is_some_and(|results| results.did_you_mean) {
+            list = list.block(Block::new().title("No match, did you mean:".italic()));
+        } else if let Some(expansion) = results.and_then(|results| results.expansion.as_ref()) {
+            list = list.block(Block::new().title(expansion_title(expansion).italic()));
+        }
+        // Positions in the built rows, which start at the offset
+        let mut window_state =
+            ListState::default().with_selected(selected.map(|selected| selected - offset));
+        StatefulWidget::render(list, list_area, buf, &mut window_state);
 
-        self.vertical_scroll_state = self.vertical_scroll_state.content_length(items_count);
+        self.vertical_scroll_state = self
+            .vertical_scroll_state
+            .content_length(items_count)
+            .position(selected.unwrap_or_default());
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
         scrollbar.render(list_area, buf, &mut self.vertical_scroll_state);
 
+        let body = match selected_item {
+            Some(item @ (SearchItem::Function(_) | SearchItem::Method(_))) => state
+                .manual
+                .source(item.entity())
+                .and_then(|path| state.function_bodies.get(path)),
+            _ => None,
+        };
+
         let preview = Paragraph::new(
-            selected_function
-                .clone()
-                .map(|function| {
-                    let description = function
-                        .description
-                        .iter()
-                        .map(|desc_node| desc_node.to_string())
-                        .map(|str| str.trim().to_string())
-                        .collect::<Vec<_>>()
-                        .join(" ");
-
-                    format!("{function}\n\n{description}")
-                })
+            selected_item
+                .map(|item| item.preview(body))
                 // .map(|code| {
                 //     use ansi_to_tui::IntoText;
                 //
@@ -213,5 +522,186 @@ impl StatefulWidget for &mut SearchModal {
         .block(Block::bordered().padding(Padding::horizontal(1)));
 
         preview.render(preview_area.inner(Margin::new(1, 0)), buf);
+
+        Paragraph::new(hints).render(hints_area, buf);
+    }
+}
+
+/// The hints as lines no wider than `width`, as many on each line as fit rather than cutting
+/// one in the middle
+fn hint_lines(hints: &[(&'static str, &'static str)], width: usize) -> Vec<Line<'static>> {
+    const SEPARATOR: &str = "  ";
+
+    let mut lines = Vec::<Line>::new();
+    let mut line_width = 0;
+    for (key, description) in hints {
+        let hint_width = key.chars().count() + 1 + description.chars().count();
+        let spans = [
+            Span::styled(*key, Style::default().bold()),
+            Span::styled(
+                format!(" {description}"),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+
+        match lines.last_mut() {
+            Some(line) if line_width + SEPARATOR.len() + hint_width <= width => {
+                line.spans.push(Span::raw(SEPARATOR));
+                line.spans.extend(spans);
+                line_width += SEPARATOR.len() + hint_width;
+            }
+            _ => {
+                lines.push(Line::from(spans.to_vec()));
+                line_width = hint_width;
+            }
+        }
+    }
+
+    lines
+}
+
+/// The first of `length` results to show in `rows`, scrolling from `offset` as little as needed to
+/// keep the selection and [`SCROLL_PADDING`] results around it in view
+fn visible_offset(offset: usize, selected: Option<usize>, rows: usize, length: usize) -> usize {
+    let rows = rows.max(1);
+    let padding = SCROLL_PADDING.min((rows - 1) / 2);
+    // Results may have been removed below the rows shown
+    let offset = offset.min(length.saturating_sub(rows));
+
+    match selected {
+        Some(selected) if selected < offset + padding => selected.saturating_sub(padding),
+        Some(selected) if selected + padding >= offset + rows => {
+            // Not past the last row, there is nothing below it to keep in view
+            (selected + padding + 1 - rows).min(length.saturating_sub(rows))
+        }
+        _ => offset,
+    }
+}
+
+/// Runs of characters of a name which share whether they matched the query and whether they are
+/// part of the namespace
+fn name_runs(name: &FullyQualifiedName, indices: &[usize]) -> Vec<(String, bool, bool)> {
+    let name_text = name.to_string();
+    let namespace_length = name_text.chars().count() - name.name.chars().count();
+    let indices = indices.iter().collect::<HashSet<_>>();
+
+    let mut runs = Vec::<(String, bool, bool)>::new();
+    for (i, char) in name_text.chars().enumerate() {
+        let (matching, is_namespace) = (indices.contains(&i), i < namespace_length);
+        match runs.last_mut() {
+            Some((run, run_matching, run_is_namespace))
+                if (*run_matching, *run_is_namespace) == (matching, is_namespace) =>
+            {
+                run.push(char)
+            }
+            _ => runs.push((char.to_string(), matching, is_namespace)),
+        }
+    }
+
+    runs
+}
+
+/// Why the names marked `≈` are listed, e.g. `≈ related to "length": count, mb_strlen`
+fn expansion_title(expansion: &Expansion) -> String {
+    format!(
+        "≈ related to \"{}\": {}",
+        expansion.word,
+        expansion
+            .names
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// A filter of the query, followed by a space to set it apart from the next one
+fn filter_chip(filter: &Filter) -> [Span<'static>; 2] {
+    let color = if filter.negated {
+        Color::LightRed
+    } else {
+        Color::LightBlue
+    };
+
+    [
+        Span::styled(
+            format!(" {filter} "),
+            Style::default().fg(Color::Black).bg(color),
+        ),
+        Span::raw(" "),
+    ]
+}
+
+fn snippet_line(snippet: &Snippet) -> Line<'static> {
+    let mut spans = Vec::<Span>::new();
+    let mut position = 0;
+    for highlight in &snippet.highlights {
+        spans.push(Span::raw(
+            snippet.text[position..highlight.start].to_string(),
+        ));
+        spans.push(Span::styled(
+            snippet.text[highlight.clone()].to_string(),
+            Style::default().fg(Color::LightRed).bold(),
+        ));
+        position = highlight.end;
+    }
+    spans.push(Span::raw(snippet.text[position..].to_string()));
+
+    Line::from(spans).style(Style::default().fg(Color::Gray))
+}
+
+#[cfg(test)]
+mod test {
+    use php_docbook_parser::name::FullyQualifiedName;
+
+    use super::{hint_lines, name_runs, visible_offset};
+
+    #[rstest::rstest]
+    #[case(0, None, 10, 100, 0)]
+    #[case(0, Some(5), 10, 100, 0)]
+    // Scrolls to keep two results below the selection
+    #[case(0, Some(8), 10, 100, 1)]
+    #[case(20, Some(21), 10, 100, 19)]
+    #[case(0, Some(99), 10, 100, 90)]
+    // The results shrank below the rows shown
+    #[case(90, None, 10, 5, 0)]
+    #[case(0, Some(3), 1, 100, 3)]
+    pub fn test_visible_offset(
+        #[case] offset: usize,
+        #[case] selected: Option<usize>,
+        #[case] rows: usize,
+        #[case] length: usize,
+        #[case] expected: usize,
+    ) {
+        assert_eq!(visible_offset(offset, selected, rows, length), expected);
+    }
+
+    #[test]
+    pub fn test_name_runs() {
+        assert_eq!(
+            name_runs(&FullyQualifiedName::from("Random\\Randomizer"), &[7, 8, 12]),
+            [
+                ("Random\\".to_string(), false, true),
+                ("Ra".to_string(), true, false),
+                ("ndo".to_string(), false, false),
+                ("m".to_string(), true, false),
+                ("izer".to_string(), false, false),
+            ]
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(80, &["[Tab] switch kind  [Ctrl+B] star"])]
+    #[case(32, &["[Tab] switch kind  [Ctrl+B] star"])]
+    #[case(31, &["[Tab] switch kind", "[Ctrl+B] star"])]
+    // A hint wider than the modal still gets its own line
+    #[case(10, &["[Tab] switch kind", "[Ctrl+B] star"])]
+    pub fn test_hint_lines(#[case] width: usize, #[case] expected: &[&str]) {
+        let lines = hint_lines(&[("[Tab]", "switch kind"), ("[Ctrl+B]", "star")], width);
+
+        assert_eq!(
+            lines.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            expected
+        );
     }
 }
@@ -0,0 +1,154 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, StatefulWidget, Widget,
+    },
+};
+
+use crate::{
+    history::{Entry, EntryKind},
+    tui::{
+        SharedState,
+        event::{EventHandler, EventHandlerResult, Message},
+    },
+};
+
+/// Starred entries, functions along with their synopsis so that their parameters are one glance
+/// away
+#[derive(Default, Debug)]
+pub struct BookmarksScreen {
+    list_state: ListState,
+    vertical_scroll_state: ScrollbarState,
+}
+
+impl EventHandler for BookmarksScreen {
+    async fn on_key_event(&mut self, key: &KeyEvent) -> EventHandlerResult {
+        match key.code {
+            KeyCode::Down => {
+                self.vertical_scroll_state.next();
+                self.list_state.select_next();
+            }
+            KeyCode::Up => {
+                self.vertical_scroll_state.prev();
+                self.list_state.select_previous();
+            }
+            KeyCode::Home => {
+                self.vertical_scroll_state.first();
+                self.list_state.select_first();
+            }
+            KeyCode::End => {
+                self.vertical_scroll_state.last();
+                self.list_state.select_last();
+            }
+            KeyCode::Enter => {
+                if let Some(selected) = self.list_state.selected() {
+                    return EventHandlerResult::HandledWithMessage(Message::OpenBookmark(selected));
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                if let Some(selected) = self.list_state.selected() {
+                    return EventHandlerResult::HandledWithMessage(Message::RemoveBookmark(
+                        selected,
+                    ));
+                }
+            }
+            KeyCode::Esc => {
+                return EventHandlerResult::HandledWithMessage(Message::GoToHomeScreen);
+            }
+            _ => return EventHandlerResult::Pass,
+        };
+
+        EventHandlerResult::Handled
+    }
+}
+
+impl StatefulWidget for &mut BookmarksScreen {
+    type State = SharedState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::bordered().title("Bookmarks ([Enter] to open, [d] to remove)");
+
+        if state.bookmarks.is_empty() {
+            Paragraph::new(
+                "Nothing is starred yet: star entries with [Ctrl+B] while searching, or [b] while browsing the manual",
+            )
+            .centered()
+            .block(block)
+            .render(area, buf);
+
+            return;
+        }
+
+        // The last bookmark may have been removed
+        if self
+            .list_state
+            .selected()
+            .is_none_or(|selected| selected >= state.bookmarks.len())
+        {
+            self.list_state.select(Some(state.bookmarks.len() - 1));
+        }
+
+        let items = state
+            .bookmarks
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let item = ListItem::new(bookmark_lines(state, entry));
+
+                if self.list_state.selected() == Some(i) {
+                    item.style(Style::new().italic().bg(Color::DarkGray))
+                } else {
+                    item
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let list = List::new(items).scroll_padding(2).block(block);
+        StatefulWidget::render(list, area, buf, &mut self.list_state);
+
+        self.vertical_scroll_state = self
+            .vertical_scroll_state
+            .content_length(state.bookmarks.len());
+        Scrollbar::new(ScrollbarOrientation::VerticalRight).render(
+            area,
+            buf,
+            &mut self.vertical_scroll_state,
+        );
+    }
+}
+
+/// The name of the entry and what it is, the synopsis and summary of functions and methods
+fn bookmark_lines(state: &SharedState, entry: &Entry) -> Vec<Line<'static>> {
+    let function = match entry.kind {
+        EntryKind::Function => state.manual.function(&entry.name),
+        EntryKind::Method => state.manual.method(&entry.name),
+        EntryKind::Variable
+        | EntryKind::Class
+        | EntryKind::Page
+        | EntryKind::Constant
+        | EntryKind::IniDirective => None,
+    };
+
+    let mut lines = match function {
+        Some(function) => function
+            .to_string()
+            .lines()
+            .map(|line| Line::from(format!("★ {line}")).bold())
+            .collect(),
+        None => vec![Line::from(format!("★ {}", entry.title(&state.manual))).bold()],
+    };
+    lines.push(
+        Line::from(match function {
+            Some(function) => format!("  {}", function.short_description),
+            None => format!("  {:?}", entry.kind),
+        })
+        .fg(Color::Gray),
+    );
+
+    lines
+}
@@ -0,0 +1,197 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, List, ListItem, ListState, Padding, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, StatefulWidget, Widget, Wrap,
+    },
+};
+
+use php_docbook_parser::chapter::Section;
+
+use crate::{
+    history::{Entry, EntryKind},
+    tui::{
+        SharedState,
+        event::{EventHandler, EventHandlerResult, Message},
+    },
+};
+
+/// Table of contents of the language reference, with the selected section on the right
+#[derive(Default, Debug)]
+pub struct ManualScreen {
+    list_state: ListState,
+    vertical_scroll_state: ScrollbarState,
+    content_scroll: u16,
+    /// Key of the selected section as of the last render, see [`Section::key`]
+    selected_key: Option<String>,
+    /// Key of the section to select on the next render
+    go_to_key: Option<String>,
+}
+
+impl ManualScreen {
+    /// Browses the manual at the section with this [key](Section::key)
+    pub fn at(key: &str) -> Self {
+        Self {
+            go_to_key: Some(key.to_string()),
+            ..Self::default()
+        }
+    }
+
+    fn select(&mut self, select: impl FnOnce(&mut ListState)) {
+        select(&mut self.list_state);
+        self.content_scroll = 0;
+    }
+}
+
+impl EventHandler for ManualScreen {
+    async fn on_key_event(&mut self, key: &KeyEvent) -> EventHandlerResult {
+        match key.code {
+            KeyCode::Down => {
+                self.vertical_scroll_state.next();
+                self.select(ListState::select_next);
+            }
+            KeyCode::Up => {
+                self.vertical_scroll_state.prev();
+                self.select(ListState::select_previous);
+            }
+            KeyCode::Home => {
+                self.vertical_scroll_state.first();
+                self.select(ListState::select_first);
+            }
+            KeyCode::End => {
+                self.vertical_scroll_state.last();
+                self.select(ListState::select_last);
+            }
+            KeyCode::PageDown => {
+                self.content_scroll = self.content_scroll.saturating_add(10);
+            }
+            KeyCode::PageUp => {
+                self.content_scroll = self.content_scroll.saturating_sub(10);
+            }
+            KeyCode::Char('b') | KeyCode::Char('B') => {
+                let Some(key) = self.selected_key.clone() else {
+                    return EventHandlerResult::Handled;
+                };
+
+                return EventHandlerResult::HandledWithMessage(Message::ToggleBookmark(Entry {
+                    kind: EntryKind::Page,
+                    name: key,
+                }));
+            }
+            KeyCode::Esc => {
+                return EventHandlerResult::HandledWithMessage(Message::GoToHomeScreen);
+            }
+            _ => return EventHandlerResult::Pass,
+        };
+
+        EventHandlerResult::Handled
+    }
+}
+
+impl StatefulWidget for &mut ManualScreen {
+    type State = SharedState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let [list_area, content_area] =
+            Layout::horizontal([Constraint::Ratio(1, 3), Constraint::Ratio(2, 3)]).split(area)[..]
+        else {
+            unreachable!()
+        };
+
+        let entries = state
+            .manual
+            .chapters()
+            .flat_map(Section::table_of_contents)
+            .collect::<Vec<_>>();
+
+        if let Some(key) = self.go_to_key.take() {
+            let section = state.manual.section(&key);
+            self.list_state
+                .select(entries.iter().position(|(_, other)| {
+                    section.is_some_and(|section| std::ptr::eq(section, *other))
+                }));
+        }
+        if self.list_state.selected().is_none() && !entries.is_empty() {
+            self.list_state.select_first();
+        }
+        let selected_section = self
+            .list_state
+            .selected()
+            .and_then(|selected| entries.get(selected))
+            .map(|(_, section)| *section);
+        self.selected_key = selected_section.map(|section| section.key().to_string());
+
+        let items = entries
+            .iter()
+            .enumerate()
+            .map(|(i, (depth, section))| {
+                let starred = state.bookmarks.contains(&Entry {
+                    kind: EntryKind::Page,
+                    name: section.key().to_string(),
+                });
+                let line = Line::from(format!(
+                    "{}{}{}",
+                    "  ".repeat(*depth),
+                    section.title,
+                    if starred { " ★" } else { "" }
+                ));
+                let item = if *depth == 0 {
+                    ListItem::new(line.bold())
+                } else {
+                    ListItem::new(line)
+                };
+
+                if self.list_state.selected() == Some(i) {
+                    item.style(Style::new().italic().bg(Color::DarkGray))
+                } else {
+                    item
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let list = List::new(items)
+            .scroll_padding(2)
+            .block(Block::bordered().title("Manual ([b] to star)"));
+        StatefulWidget::render(list, list_area, buf, &mut self.list_state);
+
+        self.vertical_scroll_state = self.vertical_scroll_state.content_length(entries.len());
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
+        scrollbar.render(list_area, buf, &mut self.vertical_scroll_state);
+
+        let content = selected_section
+            .map(|section| render_section(section, 1))
+            .unwrap_or("No manual page available".to_string());
+
+        Paragraph::new(content)
+            .wrap(Wrap::default())
+            .scroll((self.content_scroll, 0))
+            .block(Block::bordered().padding(Padding::horizontal(1)))
+            .render(content_area.inner(Margin::new(1, 0)), buf);
+    }
+}
+
+/// The section and its nested sections as text, their titles prefixed with `#` as deep as they are
+pub fn render_section(section: &Section, level: usize) -> String {
+    let mut text = format!("{} {}\n\n", "#".repeat(level), section.title);
+
+    for paragraph in &section.paragraphs {
+        text.push_str(
+            &paragraph
+                .iter()
+                .map(|node| node.to_string().trim().to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        text.push_str("\n\n");
+    }
+
+    for subsection in &section.sections {
+        text.push_str(&render_section(subsection, level + 1));
+    }
+
+    text
+}
@@ -1,13 +1,20 @@
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
-    layout::{Constraint, Layout},
-    widgets::{Paragraph, StatefulWidget, Widget},
+    layout::{Constraint, Flex, Layout},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Paragraph, StatefulWidget, Widget},
 };
 
 use super::{
     SharedState,
     event::{EventHandler, EventHandlerResult, Message},
+    modal::search_modal::SearchModalType,
 };
+use crate::history::EntryKind;
+
+pub mod bookmarks_screen;
+pub mod manual_screen;
 
 #[derive(Debug)]
 pub(super) enum Screen {
@@ -23,6 +30,9 @@ impl Default for Screen {
 #[derive(Default, Debug)]
 pub struct HomeScreen;
 
+/// Recently opened entries listed on the home screen, each reopened with its number key
+const RECENT_ENTRIES: usize = 9;
+
 /// Original Art by Donovan Bake
 /// https://www.asciiart.eu/books/books
 const BOOK_ASCII: &str = indoc::indoc! {r#"
@@ -43,9 +53,20 @@ impl StatefulWidget for &mut HomeScreen {
         buf: &mut ratatui::prelude::Buffer,
         state: &mut Self::State,
     ) {
-        let [ascii_area, page_area] =
-            Layout::vertical([Constraint::Length(6), Constraint::Fill(1)]).split(area)[..]
-        else {
+        let recent = state
+            .history
+            .opened()
+            .take(RECENT_ENTRIES)
+            .enumerate()
+            .map(|(i, entry)| Line::from(format!("[{}] {}", i + 1, entry.title(&state.manual))))
+            .collect::<Vec<_>>();
+
+        let [ascii_area, page_area, recent_area] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Length(5),
+            Constraint::Fill(1),
+        ])
+        .split(area)[..] else {
             unreachable!()
         };
 
@@ -53,27 +74,60 @@ impl StatefulWidget for &mut HomeScreen {
             .centered()
             .render(ascii_area, buf);
 
+        let progress = state.loading_progress;
+        let last_failure = state
+            .parse_failures
+            .last()
+            .map(|(path, error)| format!("\nCould not parse {}: {error}", path.display()))
+            .unwrap_or_default();
         Paragraph::new(format!(
-            "{} {}/{} definition files",
-            if state.parsed_files_snapshot.len() == state.total_files_to_parse {
+            "{} {}/{} documentation files{}{last_failure}\n\n[s] Search  [v] Search predefined variables  [m] Browse the manual  [b] Bookmarks",
+            if progress.is_done() {
                 "Parsed"
             } else {
                 "Parsing"
             },
-            state.parsed_files_snapshot.len(),
-            state.total_files_to_parse
+            progress.loaded,
+            progress.total,
+            if progress.failed > 0 {
+                format!(" ({} failed)", progress.failed)
+            } else {
+                String::new()
+            },
         ))
         .centered()
         .render(page_area, buf);
+
+        if !recent.is_empty() {
+            let [recent_area] = Layout::horizontal([Constraint::Length(40)])
+                .flex(Flex::Center)
+                .areas(recent_area);
+
+            Paragraph::new(recent)
+                .block(Block::new().title("Recent".bold()))
+                .render(recent_area, buf);
+        }
     }
 }
 
 impl EventHandler for HomeScreen {
     async fn on_key_event(&mut self, key: &KeyEvent) -> EventHandlerResult {
         match key.code {
-            KeyCode::Char('s') | KeyCode::Char('S') => {
-                EventHandlerResult::HandledWithMessage(Message::OpenFunctionSearchModal)
+            KeyCode::Char('s') | KeyCode::Char('S') => EventHandlerResult::HandledWithMessage(
+                Message::OpenSearchModal(SearchModalType::All),
+            ),
+            KeyCode::Char('v') | KeyCode::Char('V') => EventHandlerResult::HandledWithMessage(
+                Message::OpenSearchModal(SearchModalType::Only(EntryKind::Variable)),
+            ),
+            KeyCode::Char('m') | KeyCode::Char('M') => {
+                EventHandlerResult::HandledWithMessage(Message::GoToManualScreen)
+            }
+            KeyCode::Char('b') | KeyCode::Char('B') => {
+                EventHandlerResult::HandledWithMessage(Message::GoToBookmarksScreen)
             }
+            KeyCode::Char(digit @ '1'..='9') => EventHandlerResult::HandledWithMessage(
+                Message::OpenRecent(digit as usize - '1' as usize),
+            ),
             _ => EventHandlerResult::Pass,
         }
     }
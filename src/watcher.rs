@@ -0,0 +1,94 @@
+use std::{collections::BTreeSet, path::Path, time::Duration};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{DebounceEventResult, Debouncer, RecommendedCache, new_debouncer};
+use tokio::sync::mpsc;
+
+use php_docbook_parser::{
+    XmlParser,
+    manual::{PageKind, parse_page},
+};
+
+use crate::{
+    cache::{SharedCache, update_shared},
+    loader::ParseEvent,
+};
+
+/// How long a file must be left alone before it is reparsed, as editors save in several writes
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(200);
+
+/// Watches the documentation checkout at `root`, reparsing its pages as they are saved, added or
+/// removed and sending the results as [`ParseEvent::Reparsed`] and [`ParseEvent::Removed`]
+///
+/// The pages are updated in the index cache too, so that the next run does not parse them again
+///
+/// Watching stops once the returned debouncer is dropped
+pub fn watch(
+    root: &Path,
+    cache: SharedCache,
+    events: mpsc::UnboundedSender<ParseEvent>,
+) -> notify::Result<Debouncer<RecommendedWatcher, RecommendedCache>> {
+    // The loader canonicalizes paths, the pages of the manual are keyed by them
+    let root = std::fs::canonicalize(root).map_err(notify::Error::io)?;
+    let watched_root = root.clone();
+    let xml_parser = XmlParser::default();
+
+    let mut debouncer = new_debouncer(DEBOUNCE_DELAY, None, move |result: DebounceEventResult| {
+        let changes = match result {
+            Ok(changes) => changes,
+            Err(errors) => {
+                for error in errors {
+                    tracing::warn!("Could not watch {}: {error}", root.display());
+                }
+
+                return;
+            }
+        };
+
+        // Reading a file while parsing it is an access, which must not trigger another parse
+        let paths = changes
+            .into_iter()
+            .filter(|change| !matches!(change.kind, EventKind::Access(_)))
+            .flat_map(|change| change.event.paths)
+            // A single save is usually reported several times
+            .collect::<BTreeSet<_>>();
+
+        for path in paths {
+            let Some(kind) = PageKind::of(&root, &path) else {
+                continue;
+            };
+
+            // Stat-ed before parsing, a later write must not pass for the parsed version
+            let event = match std::fs::metadata(&path) {
+                Ok(metadata) => {
+                    let result = parse_page(&xml_parser, kind, &path);
+                    if let Ok(page) = &result {
+                        update_shared(&cache, |cache| {
+                            cache.insert(path.clone(), &metadata, page.clone())
+                        });
+                    }
+
+                    ParseEvent::Reparsed(path, result)
+                }
+                Err(_) => {
+                    update_shared(&cache, |cache| cache.remove(&path));
+
+                    ParseEvent::Removed(path)
+                }
+            };
+
+            // The receiving end is gone, the user quit
+            if events.send(event).is_err() {
+                return;
+            }
+        }
+
+        if let Some(Err(error)) = update_shared(&cache, |cache| cache.save()) {
+            tracing::warn!("Could not write the index cache: {error}");
+        }
+    })?;
+
+    debouncer.watch(&watched_root, RecursiveMode::Recursive)?;
+
+    Ok(debouncer)
+}